@@ -0,0 +1,54 @@
+use common::{Mesh, Quaternion, Soa3};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn mesh(n: usize) -> Mesh<f32> {
+    let mut m = Mesh::new();
+    m.add_points(
+        (0..n)
+            .map(|i| {
+                let i = i as f32;
+                [i.sin(), i.cos(), i * 1e-3]
+            })
+            .collect(),
+    );
+    m.normals = m.positions.clone();
+    m
+}
+
+fn rotate(c: &mut Criterion) {
+    let q = Quaternion::from_axis_angle([0.3, -1.0, 0.6], 1.1f32);
+    let base = mesh(100_000);
+    let mut group = c.benchmark_group("rotate 100k");
+    group.bench_function("quaternion sandwich", |b| {
+        let mut m = base.clone();
+        b.iter(|| {
+            for p in m.positions.iter_mut() {
+                *p = (q * Quaternion::fom_imag(*p) * q.conj()).imag();
+            }
+            for n in m.normals.iter_mut() {
+                *n = (q * Quaternion::fom_imag(*n) * q.conj()).imag();
+            }
+            black_box(&m);
+        })
+    });
+    group.bench_function("Mesh::rotate", |b| {
+        let mut m = base.clone();
+        b.iter(|| {
+            m.rotate(black_box(q));
+            black_box(&m);
+        })
+    });
+    group.bench_function("Soa3::rotate", |b| {
+        let mut positions = Soa3::from_points(&base.positions);
+        let mut normals = Soa3::from_points(&base.normals);
+        b.iter(|| {
+            positions.rotate(black_box(q));
+            normals.rotate(black_box(q));
+            black_box((&positions, &normals));
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, rotate);
+criterion_main!(benches);
@@ -0,0 +1,279 @@
+use crate::{
+    Animator,
+    Clip,
+    Entity,
+    Interpolation,
+    Keyframe,
+    Pose,
+    Quaternion,
+    Track,
+};
+use num_traits::Float;
+use std::cmp::Ordering;
+
+impl<T: Float + From<f32>> Keyframe<T> for [T; 3] {
+    fn lerp(
+        a: Self,
+        b: Self,
+        t: T,
+    ) -> Self {
+        let mut r = a;
+        for i in 0..3 {
+            r[i] = a[i] + (b[i] - a[i]) * t;
+        }
+        r
+    }
+
+    fn cubic(
+        p0: Self,
+        p1: Self,
+        p2: Self,
+        p3: Self,
+        t: T,
+    ) -> Self {
+        let (two, three, four, five): (T, T, T, T) =
+            (2.0.into(), 3.0.into(), 4.0.into(), 5.0.into());
+        let half: T = 0.5.into();
+        let (t2, t3) = (t * t, t * t * t);
+        let mut r = p1;
+        for i in 0..3 {
+            r[i] = half *
+                (two * p1[i] +
+                    (p2[i] - p0[i]) * t +
+                    (two * p0[i] - five * p1[i] + four * p2[i] - p3[i]) * t2 +
+                    (three * (p1[i] - p2[i]) + p3[i] - p0[i]) * t3);
+        }
+        r
+    }
+}
+
+impl<T: Float + From<f32>> Keyframe<T> for Quaternion<T> {
+    fn lerp(
+        a: Self,
+        b: Self,
+        t: T,
+    ) -> Self {
+        a.nlerp(b, t)
+    }
+
+    fn slerp(
+        a: Self,
+        b: Self,
+        t: T,
+    ) -> Self {
+        a.slerp(b, t)
+    }
+
+    fn cubic(
+        p0: Self,
+        p1: Self,
+        p2: Self,
+        p3: Self,
+        t: T,
+    ) -> Self {
+        let a = Quaternion::squad_tangent(p0, p1, p2);
+        let b = Quaternion::squad_tangent(p1, p2, p3);
+        p1.squad(a, b, p2, t)
+    }
+}
+
+impl<T: Float + From<f32>, V: Keyframe<T>> Track<T, V> {
+    /// Builds a track from `(time, value)` pairs in any order.
+    pub fn new(
+        interpolation: Interpolation,
+        mut keys: Vec<(T, V)>,
+    ) -> Self {
+        keys.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        let (times, values) = keys.into_iter().unzip();
+        Self {
+            times,
+            values,
+            interpolation,
+        }
+    }
+
+    pub fn duration(&self) -> T {
+        self.times.last().copied().unwrap_or_else(T::zero)
+    }
+
+    /// Value at `t`, holding the first and last keys outside the track.
+    /// `None` for a track without keys.
+    pub fn sample(
+        &self,
+        t: T,
+    ) -> Option<V> {
+        let n = self.times.len();
+        let v = &self.values;
+        let i = self.times.iter().take_while(|&&k| k <= t).count();
+        if n == 0 {
+            return None;
+        } else if i == 0 {
+            return Some(v[0]);
+        } else if i == n {
+            return Some(v[n - 1]);
+        }
+        let u = (t - self.times[i - 1]) / (self.times[i] - self.times[i - 1]);
+        Some(match self.interpolation {
+            Interpolation::Step => v[i - 1],
+            Interpolation::Linear => V::lerp(v[i - 1], v[i], u),
+            Interpolation::Slerp => V::slerp(v[i - 1], v[i], u),
+            Interpolation::Cubic => V::cubic(
+                v[i.saturating_sub(2)],
+                v[i - 1],
+                v[i],
+                v[(i + 1).min(n - 1)],
+                u,
+            ),
+        })
+    }
+}
+
+impl<T: Float + From<f32>> Clip<T> {
+    pub fn new(
+        position: Option<Track<T, [T; 3]>>,
+        orient: Option<Track<T, Quaternion<T>>>,
+        looping: bool,
+    ) -> Self {
+        Self {
+            position,
+            orient,
+            looping,
+        }
+    }
+
+    /// Time of the last key on any track.
+    pub fn duration(&self) -> T {
+        let p = self.position.as_ref().map_or(T::zero(), Track::duration);
+        let o = self.orient.as_ref().map_or(T::zero(), Track::duration);
+        p.max(o)
+    }
+
+    /// Maps `t` into the clip, wrapping looping clips and clamping the rest.
+    pub fn local_time(
+        &self,
+        t: T,
+    ) -> T {
+        let d = self.duration();
+        if d <= T::zero() {
+            T::zero()
+        } else if self.looping {
+            t - (t / d).floor() * d
+        } else {
+            t.max(T::zero()).min(d)
+        }
+    }
+
+    pub fn sample(
+        &self,
+        t: T,
+    ) -> Pose<T> {
+        let t = self.local_time(t);
+        Pose {
+            position: self.position.as_ref().and_then(|p| p.sample(t)),
+            orient:   self.orient.as_ref().and_then(|o| o.sample(t)),
+        }
+    }
+}
+
+impl<T: Float + From<f32>> Pose<T> {
+    /// Moves `w` of the way towards `other`. A field set on only one side is
+    /// taken from that side.
+    pub fn blend(
+        &self,
+        other: &Pose<T>,
+        w: T,
+    ) -> Pose<T> {
+        fn mix<V>(
+            a: Option<V>,
+            b: Option<V>,
+            f: impl Fn(V, V) -> V,
+        ) -> Option<V> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(f(a, b)),
+                (a, None) => a,
+                (None, b) => b,
+            }
+        }
+        Pose {
+            position: mix(self.position, other.position, |a, b| {
+                Keyframe::lerp(a, b, w)
+            }),
+            orient:   mix(self.orient, other.orient, |a, b| a.slerp(b, w)),
+        }
+    }
+
+    /// Writes the pose into `e`. The position goes to the vector part of
+    /// `e.pos.q1`, which is what [`Entity::rotate`] moves.
+    pub fn apply(
+        &self,
+        e: &mut Entity<T>,
+    ) {
+        if let Some(p) = self.position {
+            e.pos.q1 = Quaternion::fom_imag(p);
+        }
+        if let Some(o) = self.orient {
+            e.orient = o;
+        }
+    }
+}
+
+impl<T: Float + From<f32>> Animator<T> {
+    pub fn new(clip: Clip<T>) -> Self {
+        Self {
+            clip,
+            time: T::zero(),
+            speed: T::one(),
+            from: None,
+            fade: T::zero(),
+            faded: T::zero(),
+        }
+    }
+
+    /// Switches to `clip` from its start, cross-fading over `fade` time
+    /// units. A zero `fade` cuts straight to it.
+    pub fn play(
+        &mut self,
+        clip: Clip<T>,
+        fade: T,
+    ) -> &mut Self {
+        let old = std::mem::replace(&mut self.clip, clip);
+        self.from = if fade > T::zero() {
+            Some((old, self.time))
+        } else {
+            None
+        };
+        self.time = T::zero();
+        self.fade = fade;
+        self.faded = T::zero();
+        self
+    }
+
+    pub fn pose(&self) -> Pose<T> {
+        let pose = self.clip.sample(self.time);
+        match &self.from {
+            Some((c, t)) => c
+                .sample(*t)
+                .blend(&pose, (self.faded / self.fade).min(T::one())),
+            None => pose,
+        }
+    }
+
+    /// Moves forward by `dt * speed` and applies the resulting pose to `e`.
+    pub fn advance(
+        &mut self,
+        dt: T,
+        e: &mut Entity<T>,
+    ) -> &mut Self {
+        let dt = dt * self.speed;
+        self.time = self.clip.local_time(self.time + dt);
+        if let Some((c, t)) = &mut self.from {
+            *t = c.local_time(*t + dt);
+        }
+        self.faded = self.faded + dt.abs();
+        if self.faded >= self.fade {
+            self.from = None;
+        }
+        self.pose().apply(e);
+        self
+    }
+}
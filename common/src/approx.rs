@@ -0,0 +1,249 @@
+use crate::{ApproxEq, Entity, Mesh, Octonion, Quaternion};
+use num_traits::Float;
+
+impl<T: Float + From<f32>> ApproxEq for T {
+    type Epsilon = T;
+
+    fn abs_diff_eq(
+        &self,
+        other: &T,
+        epsilon: T,
+    ) -> bool {
+        *self == *other || (*self - *other).abs() <= epsilon
+    }
+
+    fn relative_eq(
+        &self,
+        other: &T,
+        epsilon: T,
+        max_relative: T,
+    ) -> bool {
+        if *self == *other {
+            return true;
+        }
+        if self.is_infinite() || other.is_infinite() {
+            return false;
+        }
+        let diff = (*self - *other).abs();
+        diff <= epsilon || diff <= self.abs().max(other.abs()) * max_relative
+    }
+
+    /// ULPs are counted at the spacing of the larger operand, which is exact
+    /// unless the two straddle a power of two.
+    fn ulps_eq(
+        &self,
+        other: &T,
+        epsilon: T,
+        max_ulps: u32,
+    ) -> bool {
+        if self.abs_diff_eq(other, epsilon) {
+            return true;
+        }
+        if self.is_sign_positive() != other.is_sign_positive() ||
+            !self.is_finite() ||
+            !other.is_finite()
+        {
+            return false;
+        }
+        let big = self.abs().max(other.abs());
+        let ulp = if big < T::min_positive_value() {
+            T::min_positive_value() * T::epsilon()
+        } else {
+            T::epsilon() * big.log2().floor().exp2()
+        };
+        let max_ulps: T = (max_ulps as f32).into();
+        (*self - *other).abs() <= ulp * max_ulps
+    }
+}
+
+fn all_slices<T: Float + From<f32>>(
+    a: &[T],
+    b: &[T],
+    f: impl Fn(&T, &T) -> bool,
+) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| f(x, y))
+}
+
+fn all_points<T: Float + From<f32>, const N: usize>(
+    a: &[[T; N]],
+    b: &[[T; N]],
+    f: impl Fn(&T, &T) -> bool,
+) -> bool {
+    a.len() == b.len() &&
+        a.iter().zip(b.iter()).all(|(x, y)| all_slices(x, y, &f))
+}
+
+impl<T: Float + From<f32>> ApproxEq for Quaternion<T> {
+    type Epsilon = T;
+
+    fn abs_diff_eq(
+        &self,
+        other: &Self,
+        epsilon: T,
+    ) -> bool {
+        all_slices(&self.val, &other.val, |a, b| a.abs_diff_eq(b, epsilon))
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: T,
+        max_relative: T,
+    ) -> bool {
+        all_slices(&self.val, &other.val, |a, b| {
+            a.relative_eq(b, epsilon, max_relative)
+        })
+    }
+
+    fn ulps_eq(
+        &self,
+        other: &Self,
+        epsilon: T,
+        max_ulps: u32,
+    ) -> bool {
+        all_slices(&self.val, &other.val, |a, b| {
+            a.ulps_eq(b, epsilon, max_ulps)
+        })
+    }
+}
+
+impl<T: Float + From<f32>> ApproxEq for Octonion<T> {
+    type Epsilon = T;
+
+    fn abs_diff_eq(
+        &self,
+        other: &Self,
+        epsilon: T,
+    ) -> bool {
+        self.q1.abs_diff_eq(&other.q1, epsilon) &&
+            self.q2.abs_diff_eq(&other.q2, epsilon)
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: T,
+        max_relative: T,
+    ) -> bool {
+        self.q1.relative_eq(&other.q1, epsilon, max_relative) &&
+            self.q2.relative_eq(&other.q2, epsilon, max_relative)
+    }
+
+    fn ulps_eq(
+        &self,
+        other: &Self,
+        epsilon: T,
+        max_ulps: u32,
+    ) -> bool {
+        self.q1.ulps_eq(&other.q1, epsilon, max_ulps) &&
+            self.q2.ulps_eq(&other.q2, epsilon, max_ulps)
+    }
+}
+
+/// Vertex attributes are compared with the tolerance; indices, scale and
+/// offset must match exactly.
+impl<T: Float + From<f32>> ApproxEq for Mesh<T> {
+    type Epsilon = T;
+
+    fn abs_diff_eq(
+        &self,
+        other: &Self,
+        epsilon: T,
+    ) -> bool {
+        self.same_topology(other) &&
+            self.all_attributes(other, |a: &T, b: &T| {
+                a.abs_diff_eq(b, epsilon)
+            })
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: T,
+        max_relative: T,
+    ) -> bool {
+        self.same_topology(other) &&
+            self.all_attributes(other, |a: &T, b: &T| {
+                a.relative_eq(b, epsilon, max_relative)
+            })
+    }
+
+    fn ulps_eq(
+        &self,
+        other: &Self,
+        epsilon: T,
+        max_ulps: u32,
+    ) -> bool {
+        self.same_topology(other) &&
+            self.all_attributes(other, |a: &T, b: &T| {
+                a.ulps_eq(b, epsilon, max_ulps)
+            })
+    }
+}
+
+impl<T: Float + From<f32>> Mesh<T> {
+    fn same_topology(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.indices == other.indices &&
+            self.scale == other.scale &&
+            self.offset == other.offset
+    }
+
+    fn all_attributes(
+        &self,
+        other: &Self,
+        f: impl Fn(&T, &T) -> bool,
+    ) -> bool {
+        all_points(&self.positions, &other.positions, &f) &&
+            all_points(&self.normals, &other.normals, &f) &&
+            self.uvs.len() == other.uvs.len() &&
+            self.uvs
+                .iter()
+                .zip(&other.uvs)
+                .all(|(a, b)| all_points(a, b, &f)) &&
+            all_points(&self.colors, &other.colors, &f) &&
+            all_points(&self.tangents, &other.tangents, &f)
+    }
+}
+
+impl<T: Float + From<f32>> ApproxEq for Entity<T> {
+    type Epsilon = T;
+
+    fn abs_diff_eq(
+        &self,
+        other: &Self,
+        epsilon: T,
+    ) -> bool {
+        self.len == other.len &&
+            self.pos.abs_diff_eq(&other.pos, epsilon) &&
+            self.orient.abs_diff_eq(&other.orient, epsilon) &&
+            self.model.abs_diff_eq(&other.model, epsilon)
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: T,
+        max_relative: T,
+    ) -> bool {
+        self.len == other.len &&
+            self.pos.relative_eq(&other.pos, epsilon, max_relative) &&
+            self.orient
+                .relative_eq(&other.orient, epsilon, max_relative) &&
+            self.model.relative_eq(&other.model, epsilon, max_relative)
+    }
+
+    fn ulps_eq(
+        &self,
+        other: &Self,
+        epsilon: T,
+        max_ulps: u32,
+    ) -> bool {
+        self.len == other.len &&
+            self.pos.ulps_eq(&other.pos, epsilon, max_ulps) &&
+            self.orient.ulps_eq(&other.orient, epsilon, max_ulps) &&
+            self.model.ulps_eq(&other.model, epsilon, max_ulps)
+    }
+}
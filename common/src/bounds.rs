@@ -0,0 +1,481 @@
+use crate::{
+    Aabb,
+    Bounds,
+    BoundsCache,
+    Entity,
+    Mesh,
+    Obb,
+    Quaternion,
+    Sphere,
+    Vec3,
+};
+use num_traits::{Float, Zero};
+use std::sync::OnceLock;
+
+impl<T: Float + From<f32>> Aabb<T> {
+    pub fn from_points(points: &[[T; 3]]) -> Self {
+        let first = points.first().copied().unwrap_or([T::zero(); 3]);
+        let (mut min, mut max) = (first, first);
+        for p in points {
+            for i in 0..3 {
+                min[i] = min[i].min(p[i]);
+                max[i] = max[i].max(p[i]);
+            }
+        }
+        Self { min, max }
+    }
+
+    pub fn center(&self) -> [T; 3] {
+        let half: T = 0.5.into();
+        ((Vec3::new(self.min) + Vec3::new(self.max)) * half).val
+    }
+
+    pub fn half_extents(&self) -> [T; 3] {
+        let half: T = 0.5.into();
+        ((Vec3::new(self.max) - Vec3::new(self.min)) * half).val
+    }
+
+    pub fn contains(
+        &self,
+        p: [T; 3],
+    ) -> bool {
+        (0..3).all(|i| self.min[i] <= p[i] && p[i] <= self.max[i])
+    }
+
+    /// Box around `self` mapped through `p -> rotation * (scale * p) +
+    /// translation`, with `rotation` a unit quaternion.
+    pub fn transform(
+        &self,
+        rotation: Quaternion<T>,
+        scale: T,
+        translation: [T; 3],
+    ) -> Self {
+        let m = rotation.to_mat3();
+        let c = moved(self.center(), rotation, scale, translation);
+        let h = self.half_extents();
+        let mut out = Self { min: c, max: c };
+        for i in 0..3 {
+            let e = (0..3).fold(T::zero(), |acc, j| acc + m[j][i].abs() * h[j]) *
+                scale.abs();
+            out.min[i] = c[i] - e;
+            out.max[i] = c[i] + e;
+        }
+        out
+    }
+}
+
+impl<T: Float + From<f32>> Sphere<T> {
+    /// Inside or within a relative `sqrt(epsilon)` of the surface.
+    pub fn contains(
+        &self,
+        p: [T; 3],
+    ) -> bool {
+        let d = (Vec3::new(p) - Vec3::new(self.center)).length();
+        d <= self.radius + T::epsilon().sqrt() * (self.radius + T::one())
+    }
+
+    /// Ritter's bounding sphere: quick, usually a few percent larger than
+    /// the minimal one.
+    pub fn ritter(points: &[[T; 3]]) -> Self {
+        let first = match points.first() {
+            Some(&p) => Vec3::new(p),
+            None => return sphere1(Vec3::zero()),
+        };
+        let farthest = |from: Vec3<T>| {
+            points.iter().map(|&p| Vec3::new(p)).fold(from, |best, p| {
+                if (p - from).length_sqr() > (best - from).length_sqr() {
+                    p
+                } else {
+                    best
+                }
+            })
+        };
+        let a = farthest(first);
+        let b = farthest(a);
+        let half: T = 0.5.into();
+        let mut c = (a + b) * half;
+        let mut r = (b - a).length() * half;
+        for &p in points {
+            let p = Vec3::new(p);
+            let d = (p - c).length();
+            if d > r {
+                let grown = (r + d) * half;
+                c = c + (p - c) * ((grown - r) / d);
+                r = grown;
+            }
+        }
+        Self {
+            center: c.val,
+            radius: r,
+        }
+    }
+
+    /// Smallest enclosing sphere, by Welzl's algorithm with the recursion
+    /// unrolled. The points are visited in a fixed pseudo-random order to
+    /// keep the expected running time linear.
+    pub fn minimal(points: &[[T; 3]]) -> Self {
+        let mut p: Vec<Vec3<T>> = points.iter().map(|&p| Vec3::new(p)).collect();
+        let mut seed = 0x2545_f491_u32;
+        for i in (1..p.len()).rev() {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            p.swap(i, seed as usize % (i + 1));
+        }
+        let mut s = match p.first() {
+            Some(&a) => sphere1(a),
+            None => return sphere1(Vec3::zero()),
+        };
+        for i in 1..p.len() {
+            if s.contains(p[i].val) {
+                continue;
+            }
+            s = sphere1(p[i]);
+            for j in 0..i {
+                if s.contains(p[j].val) {
+                    continue;
+                }
+                s = sphere2(p[i], p[j]);
+                for k in 0..j {
+                    if s.contains(p[k].val) {
+                        continue;
+                    }
+                    s = sphere3(p[i], p[j], p[k]);
+                    for l in 0..k {
+                        if !s.contains(p[l].val) {
+                            s = sphere4(p[i], p[j], p[k], p[l]);
+                        }
+                    }
+                }
+            }
+        }
+        s
+    }
+
+    /// `self` mapped through `p -> rotation * (scale * p) + translation`.
+    pub fn transform(
+        &self,
+        rotation: Quaternion<T>,
+        scale: T,
+        translation: [T; 3],
+    ) -> Self {
+        Self {
+            center: moved(self.center, rotation, scale, translation),
+            radius: self.radius * scale.abs(),
+        }
+    }
+}
+
+fn sphere1<T: Float + From<f32>>(a: Vec3<T>) -> Sphere<T> {
+    Sphere {
+        center: a.val,
+        radius: T::zero(),
+    }
+}
+
+fn sphere2<T: Float + From<f32>>(
+    a: Vec3<T>,
+    b: Vec3<T>,
+) -> Sphere<T> {
+    let half: T = 0.5.into();
+    Sphere {
+        center: ((a + b) * half).val,
+        radius: (b - a).length() * half,
+    }
+}
+
+/// Circumsphere of a triangle, or the widest pair if it is degenerate.
+fn sphere3<T: Float + From<f32>>(
+    a: Vec3<T>,
+    b: Vec3<T>,
+    c: Vec3<T>,
+) -> Sphere<T> {
+    let (ab, ac) = (b - a, c - a);
+    let n = ab.cross(ac);
+    let two: T = 2.0.into();
+    let den = two * n.length_sqr();
+    let span = ab.length_sqr().max(ac.length_sqr());
+    if den <= T::epsilon() * span * span {
+        return [sphere2(a, b), sphere2(a, c), sphere2(b, c)]
+            .iter()
+            .copied()
+            .fold(sphere1(a), |m, s| if s.radius > m.radius { s } else { m });
+    }
+    let off =
+        (n.cross(ab) * ac.length_sqr() + ac.cross(n) * ab.length_sqr()) / den;
+    Sphere {
+        center: (a + off).val,
+        radius: off.length(),
+    }
+}
+
+/// Circumsphere of a tetrahedron. Flat ones fall back to the smallest
+/// circumsphere of a face that holds all four points.
+fn sphere4<T: Float + From<f32>>(
+    a: Vec3<T>,
+    b: Vec3<T>,
+    c: Vec3<T>,
+    d: Vec3<T>,
+) -> Sphere<T> {
+    let (ab, ac, ad) = (b - a, c - a, d - a);
+    let two: T = 2.0.into();
+    let den = two * ab.dot(ac.cross(ad));
+    let span = ab.length().max(ac.length()).max(ad.length());
+    if den.abs() <= T::epsilon() * span * span * span {
+        let all = [a, b, c, d];
+        return [
+            sphere3(a, b, c),
+            sphere3(a, b, d),
+            sphere3(a, c, d),
+            sphere3(b, c, d),
+        ]
+        .iter()
+        .copied()
+        .filter(|s| all.iter().all(|p| s.contains(p.val)))
+        .fold(None, |m: Option<Sphere<T>>, s| match m {
+            Some(m) if m.radius <= s.radius => Some(m),
+            _ => Some(s),
+        })
+        .unwrap_or_else(|| sphere3(a, b, c));
+    }
+    let off = (ac.cross(ad) * ab.length_sqr() +
+        ad.cross(ab) * ac.length_sqr() +
+        ab.cross(ac) * ad.length_sqr()) /
+        den;
+    Sphere {
+        center: (a + off).val,
+        radius: off.length(),
+    }
+}
+
+impl<T: Float + From<f32>> Obb<T> {
+    /// Box along the principal axes of `points`, or the [`Aabb`] when that
+    /// turns out smaller.
+    pub fn from_points(points: &[[T; 3]]) -> Self {
+        let aabb = Aabb::from_points(points);
+        let fallback = Self {
+            center:       aabb.center(),
+            orient:       Quaternion::identity(),
+            half_extents: aabb.half_extents(),
+        };
+        if points.len() < 2 {
+            return fallback;
+        }
+        let count: T = (points.len() as f32).into();
+        let mean = points
+            .iter()
+            .fold(Vec3::zero(), |acc, &p| acc + Vec3::new(p)) /
+            count;
+        let mut cov = [[T::zero(); 3]; 3];
+        for &p in points {
+            let d = Vec3::new(p) - mean;
+            for (r, row) in cov.iter_mut().enumerate() {
+                for (c, x) in row.iter_mut().enumerate() {
+                    *x = *x + d[r] * d[c];
+                }
+            }
+        }
+        let mut axes = eigenvectors(cov);
+        if axes[0].cross(axes[1]).dot(axes[2]) < T::zero() {
+            axes[2] = -axes[2];
+        }
+        let mut lo = [T::infinity(); 3];
+        let mut hi = [T::neg_infinity(); 3];
+        for &p in points {
+            for i in 0..3 {
+                let x = axes[i].dot(Vec3::new(p));
+                lo[i] = lo[i].min(x);
+                hi[i] = hi[i].max(x);
+            }
+        }
+        let half: T = 0.5.into();
+        let center = (0..3).fold(Vec3::zero(), |acc, i| {
+            acc + axes[i] * ((lo[i] + hi[i]) * half)
+        });
+        let obb = Self {
+            center:       center.val,
+            orient:       Quaternion::from_mat3([
+                axes[0].val,
+                axes[1].val,
+                axes[2].val,
+            ])
+            .u(),
+            half_extents: [0, 1, 2].map(|i| (hi[i] - lo[i]) * half),
+        };
+        if obb.volume() < fallback.volume() {
+            obb
+        } else {
+            fallback
+        }
+    }
+
+    pub fn volume(&self) -> T {
+        let h = self.half_extents;
+        let eight: T = 8.0.into();
+        eight * h[0] * h[1] * h[2]
+    }
+
+    /// Local axes as columns of a rotation matrix.
+    pub fn axes(&self) -> [[T; 3]; 3] { self.orient.to_mat3() }
+
+    pub fn contains(
+        &self,
+        p: [T; 3],
+    ) -> bool {
+        let local = self
+            .orient
+            .conj()
+            .rotate((Vec3::new(p) - Vec3::new(self.center)).val);
+        let tol = T::epsilon().sqrt();
+        (0..3).all(|i| {
+            local[i].abs() <= self.half_extents[i] * (T::one() + tol) + tol
+        })
+    }
+
+    /// `self` mapped through `p -> rotation * (scale * p) + translation`.
+    pub fn transform(
+        &self,
+        rotation: Quaternion<T>,
+        scale: T,
+        translation: [T; 3],
+    ) -> Self {
+        let s = scale.abs();
+        let h = self.half_extents;
+        Self {
+            center:       moved(self.center, rotation, scale, translation),
+            orient:       rotation * self.orient,
+            half_extents: [h[0] * s, h[1] * s, h[2] * s],
+        }
+    }
+}
+
+/// Unit eigenvectors of a symmetric matrix by cyclic Jacobi rotations.
+fn eigenvectors<T: Float + From<f32>>(mut a: [[T; 3]; 3]) -> [Vec3<T>; 3] {
+    let mut v = [[T::zero(); 3]; 3];
+    for (i, row) in v.iter_mut().enumerate() {
+        row[i] = T::one();
+    }
+    let two: T = 2.0.into();
+    for _ in 0..32 {
+        let (p, q) =
+            [(0, 1), (0, 2), (1, 2)]
+                .iter()
+                .copied()
+                .fold((0, 1), |m, (p, q)| {
+                    if a[p][q].abs() > a[m.0][m.1].abs() {
+                        (p, q)
+                    } else {
+                        m
+                    }
+                });
+        let scale = a[p][p].abs() + a[q][q].abs();
+        if a[p][q].abs() <= T::epsilon() * scale || a[p][q] == T::zero() {
+            break;
+        }
+        let theta = (a[q][q] - a[p][p]) / (two * a[p][q]);
+        let t =
+            theta.signum() / (theta.abs() + (theta * theta + T::one()).sqrt());
+        let c = T::one() / (t * t + T::one()).sqrt();
+        let s = t * c;
+        for row in a.iter_mut().chain(v.iter_mut()) {
+            let (kp, kq) = (row[p], row[q]);
+            row[p] = c * kp - s * kq;
+            row[q] = s * kp + c * kq;
+        }
+        let (rp, rq) = (a[p], a[q]);
+        for k in 0..3 {
+            a[p][k] = c * rp[k] - s * rq[k];
+            a[q][k] = s * rp[k] + c * rq[k];
+        }
+    }
+    [0, 1, 2].map(|c| Vec3::new([v[0][c], v[1][c], v[2][c]]).normalize())
+}
+
+fn moved<T: Float + From<f32>>(
+    p: [T; 3],
+    rotation: Quaternion<T>,
+    scale: T,
+    translation: [T; 3],
+) -> [T; 3] {
+    let p = Vec3::new(p) * scale;
+    (Vec3::new(rotation.rotate(p.val)) + Vec3::new(translation)).val
+}
+
+impl<T: Float + From<f32>> Bounds<T> {
+    pub fn from_points(points: &[[T; 3]]) -> Self {
+        Self {
+            aabb:   Aabb::from_points(points),
+            sphere: Sphere::minimal(points),
+            obb:    Obb::from_points(points),
+        }
+    }
+
+    /// Every volume mapped through `p -> rotation * (scale * p) +
+    /// translation`, with `rotation` a unit quaternion.
+    pub fn transform(
+        &self,
+        rotation: Quaternion<T>,
+        scale: T,
+        translation: [T; 3],
+    ) -> Self {
+        Self {
+            aabb:   self.aabb.transform(rotation, scale, translation),
+            sphere: self.sphere.transform(rotation, scale, translation),
+            obb:    self.obb.transform(rotation, scale, translation),
+        }
+    }
+}
+
+impl<T: Float + From<f32>> Default for BoundsCache<T> {
+    fn default() -> Self { Self(OnceLock::new()) }
+}
+impl<T: Float + From<f32>> PartialEq for BoundsCache<T> {
+    fn eq(
+        &self,
+        _: &Self,
+    ) -> bool {
+        true
+    }
+}
+
+impl<T: Float + From<f32>> Mesh<T> {
+    /// Model-space bounds of `positions`, ignoring `scale` and `offset`.
+    /// Computed on first use and cached until a method that moves vertices
+    /// runs. Bounds cached for a different number of positions are not
+    /// used; call [`invalidate_bounds`](Self::invalidate_bounds) after
+    /// editing `positions` directly.
+    pub fn bounds(&self) -> Bounds<T> {
+        let n = self.positions.len();
+        match self
+            .bounds
+            .0
+            .get_or_init(|| (n, Bounds::from_points(&self.positions)))
+        {
+            &(len, b) if len == n => b,
+            _ => Bounds::from_points(&self.positions),
+        }
+    }
+
+    pub fn invalidate_bounds(&mut self) -> &mut Self {
+        self.bounds.0.take();
+        self
+    }
+}
+
+impl<T: Float + From<f32>> Entity<T> {
+    /// World-space bounds: the model's bounds scaled by `model.scale`,
+    /// shifted by `model.offset`, rotated by `orient` and moved to the
+    /// vector part of `pos.q1`. A zero `orient` counts as no rotation.
+    pub fn bounds(&self) -> Bounds<T> {
+        let r = if self.orient.is_zero() {
+            Quaternion::identity()
+        } else {
+            self.orient.u()
+        };
+        let t = Vec3::new(r.rotate(self.model.offset)) +
+            Vec3::new(self.pos.q1.imag());
+        self.model
+            .bounds()
+            .transform(r, self.model.scale.into(), t.val)
+    }
+}
@@ -0,0 +1,232 @@
+use crate::{CayleyDickson, Hypercomplex, Octonion, Quaternion, WhichFloat};
+use num_traits::{Float, One, Zero};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use vulkano::pipeline::vertex::{VertexMember, VertexMemberTy};
+
+impl<T: Float + From<f32>> Hypercomplex for T {
+    type Scalar = T;
+
+    const DIM: usize = 1;
+
+    fn from_scalar(s: T) -> Self { s }
+
+    fn conj(self) -> Self { self }
+
+    fn scale(
+        self,
+        s: T,
+    ) -> Self {
+        self * s
+    }
+
+    fn norm_sqr(self) -> T { self * self }
+
+    fn write_components(
+        &self,
+        out: &mut [T],
+    ) {
+        out[0] = *self;
+    }
+
+    fn from_components(inp: &[T]) -> Self { inp[0] }
+}
+
+impl<T: Float + From<f32>> Hypercomplex for Quaternion<T> {
+    type Scalar = T;
+
+    const DIM: usize = 4;
+
+    fn from_scalar(s: T) -> Self {
+        Quaternion::new([s, T::zero(), T::zero(), T::zero()])
+    }
+
+    fn conj(self) -> Self { Quaternion::conj(self) }
+
+    fn scale(
+        self,
+        s: T,
+    ) -> Self {
+        self * s
+    }
+
+    fn norm_sqr(self) -> T { self.dot(self) }
+
+    fn write_components(
+        &self,
+        out: &mut [T],
+    ) {
+        out[0..4].copy_from_slice(&self.val);
+    }
+
+    fn from_components(inp: &[T]) -> Self { Quaternion::from_slice(&inp[0..4]) }
+}
+
+impl<A: Hypercomplex> Hypercomplex for CayleyDickson<A> {
+    type Scalar = A::Scalar;
+
+    const DIM: usize = 2 * A::DIM;
+
+    fn from_scalar(s: A::Scalar) -> Self {
+        Self {
+            re: A::from_scalar(s),
+            im: A::from_scalar(A::Scalar::zero()),
+        }
+    }
+
+    fn conj(self) -> Self {
+        Self {
+            re: self.re.conj(),
+            im: -self.im,
+        }
+    }
+
+    fn scale(
+        self,
+        s: A::Scalar,
+    ) -> Self {
+        Self {
+            re: self.re.scale(s),
+            im: self.im.scale(s),
+        }
+    }
+
+    fn norm_sqr(self) -> A::Scalar { self.re.norm_sqr() + self.im.norm_sqr() }
+
+    fn write_components(
+        &self,
+        out: &mut [A::Scalar],
+    ) {
+        self.re.write_components(&mut out[..A::DIM]);
+        self.im.write_components(&mut out[A::DIM..]);
+    }
+
+    fn from_components(inp: &[A::Scalar]) -> Self {
+        Self {
+            re: A::from_components(&inp[..A::DIM]),
+            im: A::from_components(&inp[A::DIM..]),
+        }
+    }
+}
+
+impl<A: Hypercomplex> CayleyDickson<A> {
+    pub fn new(
+        re: A,
+        im: A,
+    ) -> Self {
+        Self { re, im }
+    }
+
+    pub fn identity() -> Self { Self::from_scalar(A::Scalar::one()) }
+
+    pub fn from_slice(inp: &[A::Scalar]) -> Self { Self::from_components(inp) }
+
+    pub fn to_vec(&self) -> Vec<A::Scalar> {
+        let mut v = vec![A::Scalar::zero(); Self::DIM];
+        self.write_components(&mut v);
+        v
+    }
+
+    pub fn norm(self) -> A::Scalar { self.norm_sqr().sqrt() }
+
+    /// `conj / |x|^2`. Past the octonions this is only a one-sided inverse in
+    /// general, since sedenions have zero divisors.
+    pub fn inverse(self) -> Self {
+        self.conj().scale(A::Scalar::one() / self.norm_sqr())
+    }
+}
+
+unsafe impl<A: Hypercomplex> VertexMember for CayleyDickson<A>
+where
+    A::Scalar: WhichFloat,
+{
+    fn format() -> (VertexMemberTy, usize) {
+        (A::Scalar::vmt(), A::Scalar::vms())
+    }
+}
+
+impl<T: Float + From<f32>> From<Quaternion<T>>
+    for CayleyDickson<CayleyDickson<T>>
+{
+    fn from(q: Quaternion<T>) -> Self { Self::from_components(&q.val) }
+}
+impl<T: Float + From<f32>> From<CayleyDickson<CayleyDickson<T>>>
+    for Quaternion<T>
+{
+    fn from(c: CayleyDickson<CayleyDickson<T>>) -> Self {
+        Quaternion::new([c.re.re, c.re.im, c.im.re, c.im.im])
+    }
+}
+impl<T: Float + From<f32>> From<Octonion<T>> for CayleyDickson<Quaternion<T>> {
+    fn from(o: Octonion<T>) -> Self { Self { re: o.q1, im: o.q2 } }
+}
+impl<T: Float + From<f32>> From<CayleyDickson<Quaternion<T>>> for Octonion<T> {
+    fn from(c: CayleyDickson<Quaternion<T>>) -> Self {
+        Octonion { q1: c.re, q2: c.im }
+    }
+}
+
+impl<A: Hypercomplex> Mul<CayleyDickson<A>> for CayleyDickson<A> {
+    type Output = CayleyDickson<A>;
+
+    fn mul(
+        self,
+        rhs: CayleyDickson<A>,
+    ) -> CayleyDickson<A> {
+        Self {
+            re: self.re * rhs.re - rhs.im.conj() * self.im,
+            im: rhs.im * self.re + self.im * rhs.re.conj(),
+        }
+    }
+}
+
+impl<A: Hypercomplex> Add<CayleyDickson<A>> for CayleyDickson<A> {
+    type Output = CayleyDickson<A>;
+
+    fn add(
+        self,
+        rhs: CayleyDickson<A>,
+    ) -> CayleyDickson<A> {
+        Self {
+            re: self.re + rhs.re,
+            im: self.im + rhs.im,
+        }
+    }
+}
+
+impl<A: Hypercomplex> Sub<CayleyDickson<A>> for CayleyDickson<A> {
+    type Output = CayleyDickson<A>;
+
+    fn sub(
+        self,
+        rhs: CayleyDickson<A>,
+    ) -> CayleyDickson<A> {
+        Self {
+            re: self.re - rhs.re,
+            im: self.im - rhs.im,
+        }
+    }
+}
+
+impl<A: Hypercomplex> Div<CayleyDickson<A>> for CayleyDickson<A> {
+    type Output = CayleyDickson<A>;
+
+    /// Right division, `self * rhs^-1`.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(
+        self,
+        rhs: CayleyDickson<A>,
+    ) -> CayleyDickson<A> {
+        self * rhs.inverse()
+    }
+}
+
+impl<A: Hypercomplex> Neg for CayleyDickson<A> {
+    type Output = CayleyDickson<A>;
+
+    fn neg(self) -> CayleyDickson<A> {
+        Self {
+            re: -self.re,
+            im: -self.im,
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use crate::{DualQuaternion, Octonion, Quaternion, WhichFloat};
+use num_traits::{Float, Zero};
+use std::ops::{Add, Mul, Neg};
+use vulkano::pipeline::vertex::{VertexMember, VertexMemberTy};
+
+impl<T: Float + From<f32>> DualQuaternion<T> {
+    pub fn new(
+        real: Quaternion<T>,
+        dual: Quaternion<T>,
+    ) -> Self {
+        Self { real, dual }
+    }
+
+    pub fn identity() -> Self {
+        Self {
+            real: Quaternion::identity(),
+            dual: Quaternion::zero(),
+        }
+    }
+
+    /// Rotation by `rotation` followed by translation by `translation`.
+    pub fn from_rotation_translation(
+        rotation: Quaternion<T>,
+        translation: [T; 3],
+    ) -> Self {
+        let half: T = 0.5.into();
+        Self {
+            real: rotation,
+            dual: Quaternion::fom_imag(translation) * rotation * half,
+        }
+    }
+
+    pub fn from_rotation(rotation: Quaternion<T>) -> Self {
+        Self {
+            real: rotation,
+            dual: Quaternion::zero(),
+        }
+    }
+
+    pub fn from_translation(translation: [T; 3]) -> Self {
+        Self::from_rotation_translation(Quaternion::identity(), translation)
+    }
+
+    pub fn rotation(&self) -> Quaternion<T> { self.real.u() }
+
+    pub fn translation(&self) -> [T; 3] {
+        let two: T = 2.0.into();
+        (self.dual * self.real.conj() * (two / self.real.dot(self.real))).imag()
+    }
+
+    /// Quaternion conjugate of both parts. For unit dual quaternions this is
+    /// the inverse transform.
+    pub fn conj(self) -> Self {
+        Self {
+            real: self.real.conj(),
+            dual: self.dual.conj(),
+        }
+    }
+
+    pub fn inverse(self) -> Self {
+        let n = self.normalize();
+        n.conj()
+    }
+
+    /// Scales to a unit real part and removes the component of `dual` that
+    /// is not orthogonal to `real`.
+    pub fn normalize(self) -> Self {
+        let n = self.real.n();
+        let real = self.real / n;
+        let dual = self.dual / n;
+        Self {
+            real,
+            dual: dual - real * real.dot(dual),
+        }
+    }
+
+    pub fn transform_point(
+        &self,
+        p: [T; 3],
+    ) -> [T; 3] {
+        let r = self.rotation().rotate(p);
+        let t = self.translation();
+        [r[0] + t[0], r[1] + t[1], r[2] + t[2]]
+    }
+
+    pub fn transform_vector(
+        &self,
+        v: [T; 3],
+    ) -> [T; 3] {
+        self.rotation().rotate(v)
+    }
+
+    /// Column-major homogeneous matrix of the transform.
+    pub fn to_mat4(&self) -> [[T; 4]; 4] {
+        let mut m = self.rotation().to_mat4();
+        let t = self.translation();
+        m[3][0..3].copy_from_slice(&t);
+        m
+    }
+
+    /// Raises a unit dual quaternion to the power `t` along its screw axis.
+    pub fn powf(
+        self,
+        t: T,
+    ) -> Self {
+        let two: T = 2.0.into();
+        let half: T = 0.5.into();
+        let q = self.normalize();
+        let w = q.real.val[0].max(-T::one()).min(T::one());
+        let half_angle = w.acos();
+        let s = half_angle.sin();
+        let tr = q.translation();
+        if s <= T::epsilon() {
+            return Self::from_rotation_translation(q.real.powf(t), [
+                tr[0] * t,
+                tr[1] * t,
+                tr[2] * t,
+            ]);
+        }
+        let v = q.real.imag();
+        let l = [v[0] / s, v[1] / s, v[2] / s];
+        let dist = tr[0] * l[0] + tr[1] * l[1] + tr[2] * l[2];
+        let cot = half_angle.cos() / s;
+        let cross = [
+            tr[1] * l[2] - tr[2] * l[1],
+            tr[2] * l[0] - tr[0] * l[2],
+            tr[0] * l[1] - tr[1] * l[0],
+        ];
+        let mut m = [T::zero(); 3];
+        for i in 0..3 {
+            m[i] = half * (cross[i] + (tr[i] - l[i] * dist) * cot);
+        }
+
+        let half_angle = half_angle * t;
+        let half_dist = dist * t / two;
+        let (s, c) = half_angle.sin_cos();
+        Self {
+            real: Quaternion::new([c, l[0] * s, l[1] * s, l[2] * s]),
+            dual: Quaternion::new([
+                -half_dist * s,
+                m[0] * s + l[0] * half_dist * c,
+                m[1] * s + l[1] * half_dist * c,
+                m[2] * s + l[2] * half_dist * c,
+            ]),
+        }
+    }
+
+    /// Screw linear interpolation along the shorter path.
+    pub fn sclerp(
+        self,
+        other: DualQuaternion<T>,
+        t: T,
+    ) -> Self {
+        let other = if self.real.dot(other.real) < T::zero() {
+            -other
+        } else {
+            other
+        };
+        self * (self.conj() * other).powf(t)
+    }
+
+    /// Dual quaternion linear blending. Weights need not sum to one; signs are
+    /// aligned with the first transform so blends take the shorter path.
+    pub fn dlb(blend: &[(T, DualQuaternion<T>)]) -> Self {
+        let pivot = match blend.first() {
+            Some((_, dq)) => dq.real,
+            None => return Self::identity(),
+        };
+        blend
+            .iter()
+            .fold(
+                Self::new(Quaternion::zero(), Quaternion::zero()),
+                |acc, &(w, dq)| {
+                    if pivot.dot(dq.real) < T::zero() {
+                        acc + dq * -w
+                    } else {
+                        acc + dq * w
+                    }
+                },
+            )
+            .normalize()
+    }
+}
+
+unsafe impl<T: Float + From<f32> + WhichFloat> VertexMember
+    for DualQuaternion<T>
+{
+    fn format() -> (VertexMemberTy, usize) { (T::vmt(), T::vms()) }
+}
+
+impl<T: Float + From<f32>> From<Octonion<T>> for DualQuaternion<T> {
+    fn from(o: Octonion<T>) -> DualQuaternion<T> {
+        Self {
+            real: o.q1,
+            dual: o.q2,
+        }
+    }
+}
+impl<T: Float + From<f32>> From<DualQuaternion<T>> for Octonion<T> {
+    fn from(dq: DualQuaternion<T>) -> Octonion<T> {
+        Octonion {
+            q1: dq.real,
+            q2: dq.dual,
+        }
+    }
+}
+
+impl<T: Float + From<f32>> Mul<T> for DualQuaternion<T> {
+    type Output = DualQuaternion<T>;
+
+    fn mul(
+        self,
+        rhs: T,
+    ) -> DualQuaternion<T> {
+        Self {
+            real: self.real * rhs,
+            dual: self.dual * rhs,
+        }
+    }
+}
+impl<T: Float + From<f32>> Mul<DualQuaternion<T>> for DualQuaternion<T> {
+    type Output = DualQuaternion<T>;
+
+    /// Composition: `a * b` applies `b` first, then `a`.
+    fn mul(
+        self,
+        rhs: DualQuaternion<T>,
+    ) -> DualQuaternion<T> {
+        Self {
+            real: self.real * rhs.real,
+            dual: self.real * rhs.dual + self.dual * rhs.real,
+        }
+    }
+}
+
+impl<T: Float + From<f32>> Add<DualQuaternion<T>> for DualQuaternion<T> {
+    type Output = DualQuaternion<T>;
+
+    fn add(
+        self,
+        rhs: DualQuaternion<T>,
+    ) -> DualQuaternion<T> {
+        Self {
+            real: self.real + rhs.real,
+            dual: self.dual + rhs.dual,
+        }
+    }
+}
+
+impl<T: Float + From<f32>> Neg for DualQuaternion<T> {
+    type Output = DualQuaternion<T>;
+
+    fn neg(self) -> DualQuaternion<T> {
+        Self {
+            real: -self.real,
+            dual: -self.dual,
+        }
+    }
+}
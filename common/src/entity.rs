@@ -1,4 +1,4 @@
-use crate::{Entity, Mesh, Octonion, Quaternion};
+use crate::{Clip, Drift, Entity, Mesh, Octonion, Quaternion, Renormalize};
 use num_traits::{identities::One, Float, Zero};
 
 impl<T: Float + From<f32>> Default for Entity<T> {
@@ -12,14 +12,69 @@ impl<T: Float + From<f32>> Entity<T> {
             orient: Quaternion::zero(),
             model:  Mesh::new(),
             len:    0,
+            renorm: Renormalize::default(),
+            drift:  Drift::default(),
         }
     }
 
     pub fn rotate(&mut self) -> &Self {
+        self.track_drift();
         self.pos.q1 = self.orient * self.pos * self.orient.conj();
         self
     }
 
+    /// Composes `q` onto `orient`, applied after the current orientation.
+    pub fn turn(
+        &mut self,
+        q: Quaternion<T>,
+    ) -> &mut Self {
+        self.orient = q * self.orient;
+        self.track_drift();
+        self
+    }
+
+    /// `||orient| - 1|`.
+    pub fn norm_error(&self) -> f32 {
+        (self.orient.n() - T::one())
+            .abs()
+            .to_f32()
+            .unwrap_or(f32::INFINITY)
+    }
+
+    /// Rescales `orient` to unit norm now, regardless of `renorm`.
+    pub fn renormalize(&mut self) -> &mut Self {
+        if !self.orient.is_zero() {
+            self.orient = self.orient.u();
+            self.drift.count += 1;
+        }
+        self.drift.ops = 0;
+        self
+    }
+
+    // A zero `orient` is "no rotation set" rather than drift, so it is
+    // neither counted nor corrected.
+    fn track_drift(&mut self) {
+        if self.orient.is_zero() {
+            return;
+        }
+        let err = self.norm_error();
+        self.drift.ops = self.drift.ops.saturating_add(1);
+        self.drift.max = self.drift.max.max(err);
+        if self.renorm.due(self.drift.ops, err) {
+            self.renormalize();
+        }
+    }
+
+    /// Sets `pos` and `orient` from `clip` at time `t`.
+    pub fn animate(
+        &mut self,
+        clip: &Clip<T>,
+        t: T,
+    ) -> &mut Self {
+        clip.sample(t).apply(self);
+        self
+    }
+
     pub fn pos_as_arr(&self) -> [T; 8] { self.pos.as_array() }
 
     pub fn as_vec(&self) -> &Vec<[T; 3]> { &self.model.positions }
@@ -41,4 +96,38 @@ impl<T: Float + From<f32>> Entity<T> {
         self.model.scale = new_scale;
         self
     }
+
+    pub fn set_renormalize(
+        mut self,
+        renorm: Renormalize,
+    ) -> Self {
+        self.renorm = renorm;
+        self
+    }
+}
+
+/// Every 1024 updates, or once the norm is off by more than `1e-5`.
+impl Default for Renormalize {
+    fn default() -> Self {
+        Self {
+            every:   1024,
+            epsilon: 1e-5,
+        }
+    }
+}
+
+impl Renormalize {
+    pub const NEVER: Renormalize = Renormalize {
+        every:   0,
+        epsilon: 0.0,
+    };
+
+    pub fn due(
+        &self,
+        ops: u32,
+        err: f32,
+    ) -> bool {
+        (self.every != 0 && ops >= self.every) ||
+            (self.epsilon > 0.0 && err > self.epsilon)
+    }
 }
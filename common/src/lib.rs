@@ -1,13 +1,31 @@
 #![deny(bare_trait_objects)]
+pub mod animation;
+pub mod approx;
+pub mod bounds;
+pub mod cayleydickson;
+pub mod dualquaternions;
 pub mod entity;
 pub mod managers;
+pub mod matrices;
 pub mod mesh;
 pub mod octonions;
+pub mod optimize;
 pub mod quaternions;
+#[cfg(feature = "serde")] pub mod serialization;
+pub mod simd;
+pub mod simplify;
+pub mod validate;
+pub mod vectors;
 
 use num_traits::Float;
+#[cfg(feature = "serde")] use serde::{Deserialize, Serialize};
+use std::{
+    ops::{Add, Mul, Neg, Sub},
+    sync::OnceLock,
+};
 use vulkano::pipeline::vertex::VertexMemberTy::{self, F32, F64};
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
 #[derive(Clone, Copy, Default, Debug, Eq, PartialEq)]
 pub struct Quaternion<T: Float + From<f32>> {
     pub val: [T; 4],
@@ -17,22 +35,365 @@ pub struct Octonion<T: Float + From<f32>> {
     pub q1: Quaternion<T>,
     pub q2: Quaternion<T>,
 }
+/// One Cayley–Dickson doubling of `A`: the pair `(re, im)` with
+/// `(a, b)(c, d) = (ac - d*b, da + bc*)` and `(a, b)* = (a*, -b)`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CayleyDickson<A> {
+    pub re: A,
+    pub im: A,
+}
+pub type Complex<T> = CayleyDickson<T>;
+pub type Sedenion<T> = CayleyDickson<CayleyDickson<Quaternion<T>>>;
+/// Rigid transform `real + ε dual`, where `real` is the rotation and `dual` is
+/// half the translation times `real`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DualQuaternion<T: Float + From<f32>> {
+    pub real: Quaternion<T>,
+    pub dual: Quaternion<T>,
+}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, PartialEq)]
 pub struct Entity<T: Float + From<f32>> {
     pub pos:    Octonion<T>,
     pub orient: Quaternion<T>,
     pub model:  Mesh<T>,
     pub len:    usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub renorm: Renormalize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub drift:  Drift,
+}
+/// When [`Entity`] renormalizes `orient`. A zero field disables that trigger.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Renormalize {
+    /// Renormalize after this many orientation updates.
+    pub every:   u32,
+    /// Renormalize as soon as `||orient| - 1|` exceeds this.
+    pub epsilon: f32,
+}
+/// Orientation drift seen by an [`Entity`] since it was created.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Drift {
+    /// Updates since the last renormalization.
+    pub ops:   u32,
+    /// Largest `||orient| - 1|` seen, measured before correcting it.
+    pub max:   f32,
+    /// Renormalizations performed.
+    pub count: u64,
+}
+/// How a [`Track`] fills the gap between two keyframes.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Interpolation {
+    /// Hold the earlier key.
+    Step,
+    Linear,
+    /// Constant angular speed. Same as `Linear` for positions.
+    Slerp,
+    /// Catmull–Rom through the neighbouring keys, SQUAD for orientations.
+    Cubic,
 }
+/// Keyframes of one animated value, sorted by time.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Track<T: Float + From<f32>, V> {
+    pub times:         Vec<T>,
+    pub values:        Vec<V>,
+    pub interpolation: Interpolation,
+}
+/// Position and orientation tracks played together. Either may be missing,
+/// in which case the clip leaves that field alone.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clip<T: Float + From<f32>> {
+    pub position: Option<Track<T, [T; 3]>>,
+    pub orient:   Option<Track<T, Quaternion<T>>>,
+    pub looping:  bool,
+}
+/// A sampled [`Clip`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pose<T: Float + From<f32>> {
+    pub position: Option<[T; 3]>,
+    pub orient:   Option<Quaternion<T>>,
+}
+/// Plays a [`Clip`] on an [`Entity`], cross-fading from the previous clip when
+/// a new one is started.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Animator<T: Float + From<f32>> {
+    pub clip:  Clip<T>,
+    pub time:  T,
+    pub speed: T,
+    /// Clip being faded out and its local time.
+    pub from:  Option<(Clip<T>, T)>,
+    pub fade:  T,
+    pub faded: T,
+}
+/// Indexed triangle list. Every non-empty per-vertex attribute has one entry
+/// per position.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Default, Debug, PartialEq)]
 pub struct Mesh<T: Float + From<f32>> {
     pub positions: Vec<[T; 3]>,
     pub normals:   Vec<[T; 3]>,
+    /// UV sets, each empty or one coordinate per position. Set 0 drives
+    /// [`Mesh::compute_tangents`].
+    #[cfg_attr(feature = "serde", serde(default = "Vec::new"))]
+    pub uvs:       Vec<Vec<[T; 2]>>,
+    /// Linear RGBA, empty or one per position.
+    #[cfg_attr(feature = "serde", serde(default = "Vec::new"))]
+    pub colors:    Vec<[T; 4]>,
+    /// Unit tangent in `xyz` and bitangent sign in `w`, as in MikkTSpace:
+    /// `bitangent = w * cross(normal, tangent)`.
+    #[cfg_attr(feature = "serde", serde(default = "Vec::new"))]
+    pub tangents:  Vec<[T; 4]>,
     pub indices:   Vec<u32>,
+    /// Live uniform scale. Vertex data is never pre-scaled; consumers map a
+    /// position through [`Mesh::model_matrix`], `p * scale + offset`, or
+    /// bake both with [`Mesh::apply_transform`].
     pub scale:     f32,
+    /// Live translation applied after `scale`.
     pub offset:    [T; 3],
+    /// Coarser index lists over the same vertices, finest first. See
+    /// [`Mesh::generate_lods`].
+    #[cfg_attr(feature = "serde", serde(default = "Vec::new"))]
+    pub lods:      Vec<Lod<T>>,
+    /// Filled by [`Mesh::bounds`]. Use `Default::default()` when building a
+    /// mesh by hand.
+    #[cfg_attr(feature = "serde", serde(skip, default = "BoundsCache::default"))]
+    pub bounds:    BoundsCache<T>,
+}
+/// One level of detail of a [`Mesh`].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lod<T: Float + From<f32>> {
+    pub indices: Vec<u32>,
+    /// Error reported by [`Mesh::simplify`] for this level.
+    pub error:   T,
+}
+/// Problems found by [`Mesh::validate`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MeshReport {
+    /// Indices past the last vertex.
+    pub out_of_range:     usize,
+    /// Indices after the last whole triangle.
+    pub trailing_indices: usize,
+    /// Vertices whose position is NaN or infinite.
+    pub non_finite:       usize,
+    /// Triangles that repeat a vertex or have zero area.
+    pub degenerate:       usize,
+    /// Per-vertex attributes whose length differs from `positions`.
+    pub mismatched:       Vec<&'static str>,
+    /// Vertices no triangle uses. Not an error on its own.
+    pub unused_vertices:  usize,
+}
+/// Result of [`Mesh::optimize`]. ACMR is the average number of vertex cache
+/// misses per triangle.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OptimizeReport {
+    pub vertices_before: usize,
+    pub vertices_after:  usize,
+    pub acmr_before:     f32,
+    pub acmr_after:      f32,
+}
+/// Axis-aligned box. A box built from no points is a point at the origin.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Aabb<T: Float + From<f32>> {
+    pub min: [T; 3],
+    pub max: [T; 3],
+}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sphere<T: Float + From<f32>> {
+    pub center: [T; 3],
+    pub radius: T,
+}
+/// Box whose local axes are those of `orient`, extending `half_extents`
+/// along each of them from `center`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Obb<T: Float + From<f32>> {
+    pub center:       [T; 3],
+    pub orient:       Quaternion<T>,
+    pub half_extents: [T; 3],
+}
+/// Every bounding volume of one point set.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Bounds<T: Float + From<f32>> {
+    pub aabb:   Aabb<T>,
+    pub sphere: Sphere<T>,
+    pub obb:    Obb<T>,
+}
+/// [`Bounds`] cached by [`Mesh::bounds`] with the number of positions they
+/// were computed from. Ignored by equality.
+#[derive(Clone, Debug)]
+pub struct BoundsCache<T: Float + From<f32>>(OnceLock<(usize, Bounds<T>)>);
+/// How [`Mesh::compute_normals`] shares normals between faces.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NormalMode {
+    /// One normal per face; every corner gets its own vertex.
+    Flat,
+    /// Area-weighted average of the faces around each vertex.
+    Smooth,
+    /// Smooth across edges whose faces meet at less than this angle, in
+    /// radians, and split the vertex along sharper ones.
+    Crease(f32),
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec2<T: Float + From<f32>> {
+    pub val: [T; 2],
+}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec3<T: Float + From<f32>> {
+    pub val: [T; 3],
+}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec4<T: Float + From<f32>> {
+    pub val: [T; 4],
+}
+/// Column-major, `val[col][row]`, matching GLSL `mat3`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Mat3<T: Float + From<f32>> {
+    pub val: [[T; 3]; 3],
+}
+/// Column-major, `val[col][row]`, matching GLSL `mat4`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Mat4<T: Float + From<f32>> {
+    pub val: [[T; 4]; 4],
+}
+
+/// Axis sequence for Euler angles. Rotations are extrinsic: `XYZ` rotates
+/// about the fixed X axis first, then Y, then Z, and the angles are given in
+/// that same order.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EulerOrder {
+    XYZ,
+    XZY,
+    YXZ,
+    YZX,
+    ZXY,
+    ZYX,
+    XYX,
+    XZX,
+    YXY,
+    YZY,
+    ZXZ,
+    ZYZ,
 }
 
+/// Algebras that can be doubled by [`CayleyDickson`]: the reals themselves,
+/// `Quaternion` and every doubling built on top of them.
+pub trait Hypercomplex:
+    Copy
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Neg<Output = Self>
+    + Mul<Output = Self> {
+    type Scalar: Float + From<f32>;
+    /// Number of real components.
+    const DIM: usize;
+    fn from_scalar(s: Self::Scalar) -> Self;
+    fn conj(self) -> Self;
+    fn scale(
+        self,
+        s: Self::Scalar,
+    ) -> Self;
+    fn norm_sqr(self) -> Self::Scalar;
+    /// Writes the `DIM` components into the front of `out`.
+    fn write_components(
+        &self,
+        out: &mut [Self::Scalar],
+    );
+    /// Reads `DIM` components from the front of `inp`.
+    fn from_components(inp: &[Self::Scalar]) -> Self;
+}
+
+/// Tolerant comparison for float-based types, component by component.
+pub trait ApproxEq {
+    type Epsilon: Copy;
+    /// Every component differs by at most `epsilon`.
+    fn abs_diff_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+    ) -> bool;
+    /// Every component is within `epsilon`, or within `max_relative` times
+    /// the larger magnitude.
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+        max_relative: Self::Epsilon,
+    ) -> bool;
+    /// Every component is within `epsilon`, or at most `max_ulps` units in
+    /// the last place apart.
+    fn ulps_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+        max_ulps: u32,
+    ) -> bool;
+}
+
+/// Values a [`Track`] can interpolate.
+pub trait Keyframe<T: Float + From<f32>>: Copy {
+    fn lerp(
+        a: Self,
+        b: Self,
+        t: T,
+    ) -> Self;
+    fn slerp(
+        a: Self,
+        b: Self,
+        t: T,
+    ) -> Self {
+        Self::lerp(a, b, t)
+    }
+    /// Cubic between `p1` and `p2`, with `p0` and `p3` the neighbouring keys.
+    fn cubic(
+        p0: Self,
+        p1: Self,
+        p2: Self,
+        p3: Self,
+        t: T,
+    ) -> Self;
+}
+
+/// Float types with a batched 3x3 transform kernel, see [`simd`].
+pub trait BatchFloat: Float + From<f32> {
+    /// Replaces every `(x[i], y[i], z[i])` with `m * v`; `m` is column-major.
+    /// The three slices have the same length.
+    fn transform_soa(
+        m: &[[Self; 3]; 3],
+        x: &mut [Self],
+        y: &mut [Self],
+        z: &mut [Self],
+    );
+}
+/// 3-vectors stored as structure of arrays, the layout the [`BatchFloat`]
+/// kernels stream through.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Soa3<T: Float + From<f32>> {
+    pub x: Vec<T>,
+    pub y: Vec<T>,
+    pub z: Vec<T>,
+}
+
+/// Float types with a matching vulkano vertex member type. Half floats are
+/// not supported: `VertexMemberTy` has no 16-bit float, and
+/// `vulkano_shaders` panics on 16-bit float shader inputs.
 pub trait WhichFloat: Float {
     fn vmt() -> VertexMemberTy;
     fn vms() -> usize;
@@ -50,6 +411,18 @@ impl WhichFloat for f64 {
 
 #[cfg(test)]
 mod tests {
+    fn close(
+        a: &[f64],
+        b: &[f64],
+    ) -> bool {
+        a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() < 1e-9)
+    }
+    fn same_rot(
+        a: crate::Quaternion<f64>,
+        b: crate::Quaternion<f64>,
+    ) -> bool {
+        a.rotation_eq(&b, 1e-9)
+    }
     #[test]
     fn quart() {
         use crate::Quaternion;
@@ -69,4 +442,1536 @@ mod tests {
         assert_eq!(o1 * o2, om);
         assert_eq!(o1 * o1.conj(), o1.conj() * o1);
     }
+    #[test]
+    fn which_float() {
+        use crate::WhichFloat;
+        use vulkano::pipeline::vertex::VertexMemberTy;
+        assert_eq!((f32::vmt(), f32::vms()), (VertexMemberTy::F32, 4));
+        assert_eq!((f64::vmt(), f64::vms()), (VertexMemberTy::F64, 8));
+    }
+    #[test]
+    fn orientation_drift() {
+        use crate::{Entity, Quaternion, Renormalize};
+        let step = Quaternion::from_axis_angle([0.3, 1.0, -0.2], 0.01f32);
+        let mut e = Entity::<f32>::new().set_renormalize(Renormalize::NEVER);
+        e.orient = Quaternion::identity();
+        let mut fixed = e.clone().set_renormalize(Renormalize {
+            every:   100,
+            epsilon: 0.0,
+        });
+        for _ in 0..100_000 {
+            e.turn(step * 1.00001);
+            fixed.turn(step * 1.00001);
+        }
+        assert!(e.norm_error() > 0.5);
+        assert_eq!(e.drift.count, 0);
+        assert!(fixed.norm_error() < 2e-3);
+        assert_eq!(fixed.drift.count, 1000);
+        assert!(fixed.drift.max > 9e-4 && fixed.drift.max < 2e-3);
+
+        let mut e = Entity::<f64>::new();
+        e.orient = Quaternion::new([2.0, 0.0, 0.0, 0.0]);
+        e.pos.q1 = Quaternion::fom_imag([1.0, 0.0, 0.0]);
+        e.rotate();
+        assert!(close(&e.pos.q1.val, &[0.0, 1.0, 0.0, 0.0]));
+        assert_eq!((e.drift.count, e.drift.ops, e.drift.max), (1, 0, 1.0));
+
+        let mut z = Entity::<f64>::new();
+        z.rotate();
+        assert_eq!(z.drift, Default::default());
+    }
+    #[test]
+    fn animation() {
+        use crate::{
+            Animator,
+            Clip,
+            Entity,
+            Interpolation::*,
+            Quaternion,
+            Track,
+        };
+        let keys = vec![
+            (2.0, [2.0, 0.0, 0.0]),
+            (0.0, [0.0, 0.0, 0.0]),
+            (1.0, [1.0, 2.0, 0.0]),
+            (3.0, [3.0, 0.0, 0.0]),
+        ];
+        let step = Track::new(Step, keys.clone());
+        assert_eq!(step.times, vec![0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(step.sample(1.9), Some([1.0, 2.0, 0.0]));
+        assert_eq!(step.sample(-1.0), Some([0.0; 3]));
+        assert_eq!(step.sample(9.0), Some([3.0, 0.0, 0.0]));
+        let lin = Track::new(Linear, keys.clone());
+        assert!(close(&lin.sample(1.5).unwrap(), &[1.5, 1.0, 0.0]));
+        let cub = Track::new(Cubic, keys);
+        assert!(close(&cub.sample(1.0).unwrap(), &[1.0, 2.0, 0.0]));
+        assert!(close(&cub.sample(1.5).unwrap(), &[1.5, 1.125, 0.0]));
+        assert_eq!(
+            Track::<f64, [f64; 3]>::new(Linear, vec![]).sample(0.0),
+            None
+        );
+
+        let z = |a| Quaternion::from_axis_angle([0.0, 0.0, 1.0], a);
+        let spin =
+            vec![(0.0, z(0.0)), (1.0, z(1.0)), (2.0, z(2.0)), (3.0, z(3.0))];
+        let slerp = Track::new(Slerp, spin.clone());
+        assert!(same_rot(slerp.sample(0.25).unwrap(), z(0.25)));
+        let squad = Track::new(Cubic, spin);
+        assert!(same_rot(squad.sample(1.0).unwrap(), z(1.0)));
+        assert!(same_rot(squad.sample(1.5).unwrap(), z(1.5)));
+
+        let mut clip = Clip::new(Some(lin), Some(slerp), true);
+        assert_eq!(clip.duration(), 3.0);
+        assert!(close(&[clip.local_time(5.5), clip.local_time(-1.0)], &[
+            2.5, 2.0
+        ]));
+        let mut e = Entity::<f64>::new();
+        e.animate(&clip, 4.5);
+        assert!(close(&e.pos.q1.val, &[0.0, 1.5, 1.0, 0.0]));
+        assert!(same_rot(e.orient, z(1.5)));
+        clip.looping = false;
+        assert_eq!(clip.local_time(5.5), 3.0);
+
+        let still = Clip::new(
+            Some(Track::new(Step, vec![(0.0, [4.0, 0.0, 0.0])])),
+            None,
+            false,
+        );
+        let mut anim = Animator::new(clip);
+        anim.advance(0.5, &mut e).play(still, 1.0);
+        anim.advance(0.25, &mut e);
+        assert!(close(&e.pos.q1.imag(), &[1.5625, 1.125, 0.0]));
+        assert!(same_rot(e.orient, z(0.75)));
+        anim.advance(1.0, &mut e);
+        assert_eq!(anim.from, None);
+        assert!(close(&e.pos.q1.imag(), &[4.0, 0.0, 0.0]));
+        assert!(same_rot(e.orient, z(0.75)));
+    }
+    #[test]
+    fn normals() {
+        use crate::{Mesh, NormalMode};
+        let mut cube = Mesh::<f64>::new();
+        cube.add_points(
+            (0..8)
+                .map(|i| {
+                    let c = |b| if i & b != 0 { 1.0 } else { -1.0 };
+                    [c(4), c(2), c(1)]
+                })
+                .collect(),
+        );
+        let quads = [
+            [4, 6, 7, 5],
+            [0, 1, 3, 2],
+            [2, 3, 7, 6],
+            [0, 4, 5, 1],
+            [1, 5, 7, 3],
+            [0, 2, 6, 4],
+        ];
+        for q in quads.iter() {
+            cube.indices.extend(&[q[0], q[1], q[2], q[0], q[2], q[3]]);
+        }
+        let dot =
+            |a: [f64; 3], b: [f64; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+        let faceted = |m: &Mesh<f64>| {
+            m.indices.iter().all(|&i| {
+                let (n, p) = (m.normals[i as usize], m.positions[i as usize]);
+                close(&[dot(n, p), n.iter().map(|x| x.abs()).sum()], &[1.0, 1.0])
+            })
+        };
+
+        let mut smooth = cube.clone();
+        smooth.compute_normals(NormalMode::Smooth);
+        assert_eq!(
+            (smooth.positions.len(), &smooth.indices),
+            (8, &cube.indices)
+        );
+        for (n, p) in smooth.normals.iter().zip(&smooth.positions) {
+            assert!(close(&[dot(*n, *n)], &[1.0]));
+            assert!((0..3).all(|k| n[k] * p[k] > 0.0));
+        }
+        let mut flat = cube.clone();
+        flat.compute_normals(NormalMode::Flat);
+        assert_eq!((flat.positions.len(), flat.normals.len()), (36, 36));
+        assert!(faceted(&flat));
+        let mut crease = cube.clone();
+        crease.compute_normals(NormalMode::Crease(std::f32::consts::FRAC_PI_3));
+        assert_eq!((crease.positions.len(), crease.normals.len()), (24, 24));
+        assert_eq!(crease.positions[..8], cube.positions[..]);
+        assert!(faceted(&crease));
+        let mut wide = cube.clone();
+        wide.compute_normals(NormalMode::Crease(std::f32::consts::PI));
+        assert_eq!(wide.positions, smooth.positions);
+        assert_eq!(wide.indices, smooth.indices);
+        let flat_n: Vec<f64> = wide.normals.iter().flatten().copied().collect();
+        let smooth_n: Vec<f64> =
+            smooth.normals.iter().flatten().copied().collect();
+        assert!(close(&flat_n, &smooth_n));
+
+        let obj = tobj::Mesh {
+            positions:   cube
+                .positions
+                .iter()
+                .flatten()
+                .map(|&x| x as f32)
+                .collect(),
+            normals:     vec![],
+            texcoords:   vec![],
+            indices:     cube.indices.clone(),
+            material_id: None,
+        };
+        let loaded = Mesh::<f64>::from_tobj_to_mesh(&obj, [0.0; 3], 1.0);
+        assert_eq!(loaded.normals.len(), loaded.positions.len());
+        assert!(faceted(&loaded));
+    }
+    #[test]
+    fn tangents() {
+        use crate::Mesh;
+        let mut quad = Mesh::<f64>::new();
+        quad.add_points(vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ]);
+        quad.indices = vec![0, 1, 2, 0, 2, 3];
+        quad.normals = vec![[0.0, 0.0, 1.0]; 4];
+        assert!(quad.compute_tangents().tangents.is_empty());
+        let tangents = |m: &mut Mesh<f64>, uv: fn([f64; 3]) -> [f64; 2]| {
+            m.uvs = vec![m.positions.iter().map(|&p| uv(p)).collect()];
+            m.compute_tangents().tangents.clone()
+        };
+        let all = |t: Vec<[f64; 4]>, r: [f64; 4]| t.iter().all(|t| close(t, &r));
+
+        let mut m = quad.clone();
+        assert!(all(tangents(&mut m, |p| [p[0], p[1]]), [
+            1.0, 0.0, 0.0, 1.0
+        ]));
+        assert!(all(tangents(&mut m, |p| [1.0 - p[0], p[1]]), [
+            -1.0, 0.0, 0.0, -1.0
+        ]));
+        assert!(all(tangents(&mut m, |p| [p[1], -p[0]]), [
+            0.0, 1.0, 0.0, 1.0
+        ]));
+        assert!(all(tangents(&mut m, |p| [p[0] + p[1], p[1]]), [
+            1.0, 0.0, 0.0, 1.0
+        ]));
+        assert!(all(tangents(&mut m, |p| [0.0 * p[0], 0.0]), [
+            1.0, 0.0, 0.0, 1.0
+        ]));
+        m.normals = vec![[0.6, 0.0, 0.8]; 4];
+        assert!(all(tangents(&mut m, |p| [p[0], p[1]]), [
+            0.8, 0.0, -0.6, 1.0
+        ]));
+
+        m.rotate(crate::Quaternion::from_axis_angle(
+            [0.0, 0.0, 1.0],
+            std::f64::consts::FRAC_PI_2,
+        ));
+        assert!(all(m.tangents.clone(), [0.0, 0.8, -0.6, 1.0]));
+    }
+    #[test]
+    fn tangents_match_mikktspace() {
+        use crate::Mesh;
+        // Per-corner output of the reference implementation (through
+        // bevy_mikktspace, in f32); every corner of a vertex agreed.
+        let expected = [
+            [0.8887686, 0.37991974, -0.25642002, -1.0],
+            [0.89417326, -0.44676477, -0.029247975, -1.0],
+            [0.8924871, -0.39880797, -0.21075809, -1.0],
+            [0.861003, 0.39102435, -0.32522884, -1.0],
+            [0.97125006, -0.05385848, -0.23188913, -1.0],
+        ];
+        let mut m = Mesh::<f64>::new();
+        m.positions = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.1, 0.0],
+            [1.2, 0.0, 1.0],
+            [0.0, 0.2, 1.1],
+            [0.5, 0.6, 0.45],
+        ];
+        m.normals = vec![
+            [-0.4583017, 0.74520594, -0.48438388],
+            [0.38443345, 0.7996216, -0.46132016],
+            [0.44911522, 0.8291358, 0.33291057],
+            [-0.280828, 0.89864945, 0.3369936],
+            [0.043006066, 0.99774104, -0.05160729],
+        ];
+        m.uvs = vec![vec![[0.0, 0.0], [1.0, 0.1], [0.9, 1.0], [-0.1, 0.8], [
+            0.45, 0.5,
+        ]]];
+        m.indices = vec![0, 4, 1, 1, 4, 2, 2, 4, 3, 3, 4, 0];
+        let near = |a: &[f64], b: &[f64]| {
+            a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-6)
+        };
+        m.compute_tangents();
+        for (t, e) in m.tangents.iter().zip(&expected) {
+            assert!(near(t, e), "{:?} {:?}", t, e);
+        }
+        // Mirroring U flips the tangent and the bitangent sign.
+        for uv in &mut m.uvs[0] {
+            uv[0] = 1.0 - uv[0];
+        }
+        m.compute_tangents();
+        for (t, e) in m.tangents.iter().zip(&expected) {
+            assert!(near(t, &e.map(|x| -x)), "{:?} {:?}", t, e);
+        }
+    }
+    #[test]
+    fn uvs_and_colors() {
+        use crate::{Mesh, NormalMode};
+        let obj = tobj::Mesh {
+            positions:   vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+            normals:     vec![],
+            texcoords:   vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0],
+            indices:     vec![0, 1, 2],
+            material_id: None,
+        };
+        let mut m = Mesh::<f64>::from_tobj_to_mesh(&obj, [0.0; 3], 1.0);
+        assert_eq!(m.uvs, vec![vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]]);
+        assert_eq!(m.tangents, vec![[1.0, 0.0, 0.0, 1.0]; 3]);
+        assert_eq!(m.mismatched_attribute(), None);
+
+        m.colors = vec![[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0]];
+        assert_eq!(m.mismatched_attribute(), Some("colors"));
+        m.colors.push([0.0, 0.0, 1.0, 1.0]);
+        m.uvs.push(vec![[0.5, 0.5]; 3]);
+        m.indices.extend(&[0, 2, 1]);
+        m.compute_normals(NormalMode::Flat);
+        assert_eq!(m.mismatched_attribute(), None);
+        assert_eq!(m.positions.len(), 6);
+        assert_eq!(m.uvs[0][4], [0.0, 1.0]);
+        assert_eq!(m.uvs[1], vec![[0.5, 0.5]; 6]);
+        assert_eq!(m.colors[5], [0.0, 1.0, 0.0, 1.0]);
+
+        let short = tobj::Mesh {
+            texcoords: vec![0.0, 0.0],
+            ..obj
+        };
+        let m = Mesh::<f64>::from_tobj_to_mesh(&short, [0.0; 3], 1.0);
+        assert!(m.uvs.is_empty() && m.tangents.is_empty());
+    }
+    #[test]
+    fn bounds() {
+        use crate::{Aabb, Entity, Mesh, Obb, Quaternion, Sphere};
+        let corners: Vec<[f64; 3]> = (0..8)
+            .map(|i| {
+                let c = |b, h: f64| if i & b != 0 { h } else { -h };
+                [c(4, 3.0), c(2, 1.0), c(1, 0.5)]
+            })
+            .collect();
+        let aabb = Aabb::from_points(&corners);
+        assert_eq!((aabb.min, aabb.max), ([-3.0, -1.0, -0.5], [3.0, 1.0, 0.5]));
+        let minimal = Sphere::minimal(&corners);
+        assert!(close(&minimal.center, &[0.0; 3]));
+        assert!(close(&[minimal.radius], &[10.25f64.sqrt()]));
+        let ritter = Sphere::ritter(&corners);
+        assert!(ritter.radius >= minimal.radius);
+        assert!(corners.iter().all(|&p| ritter.contains(p)));
+
+        let tetra = [
+            [1.0, 1.0, 1.0],
+            [1.0, -1.0, -1.0],
+            [-1.0, 1.0, -1.0],
+            [-1.0, -1.0, 1.0],
+            [0.2, 0.1, -0.3],
+        ];
+        let s = Sphere::minimal(&tetra);
+        assert!(
+            close(&s.center, &[0.0; 3]) && close(&[s.radius], &[3f64.sqrt()])
+        );
+        let flat = [[1.0, 0.0, 0.0], [-1.0, 0.0, 0.0], [0.0, 0.5, 0.0], [
+            0.0, -0.2, 0.0,
+        ]];
+        let s = Sphere::minimal(&flat);
+        assert!(close(&s.center, &[0.0; 3]) && close(&[s.radius], &[1.0]));
+        assert_eq!(Sphere::<f64>::minimal(&[]), Sphere::default());
+
+        let q = Quaternion::from_euler(crate::EulerOrder::XYZ, [0.3, -0.7, 1.1]);
+        let moved: Vec<[f64; 3]> = corners
+            .iter()
+            .map(|&p| {
+                let r = q.rotate(p);
+                [r[0] + 1.0, r[1] - 2.0, r[2] + 0.5]
+            })
+            .collect();
+        let obb = Obb::from_points(&moved);
+        let mut h = obb.half_extents;
+        h.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!(close(&h, &[0.5, 1.0, 3.0]));
+        assert!(close(&obb.center, &[1.0, -2.0, 0.5]));
+        assert!(moved.iter().all(|&p| obb.contains(p)));
+        assert!(!obb.contains([1.0, -2.0, 4.0]));
+        assert!(close(&[obb.volume()], &[12.0]));
+
+        let mut m = Mesh::<f64>::new();
+        m.add_points(corners.clone());
+        let b = m.bounds();
+        assert_eq!(b.aabb, aabb);
+        m.add_points(vec![[10.0, 0.0, 0.0]]);
+        assert_eq!(m.bounds().aabb.max[0], 10.0);
+        m.positions.pop();
+        assert_eq!(m.bounds(), b);
+        assert_eq!(m.invalidate_bounds().bounds(), b);
+        m.positions[0][0] = -20.0;
+        assert_eq!(m.bounds(), b);
+        assert_eq!(m.invalidate_bounds().bounds().aabb.min[0], -20.0);
+        m.positions[0][0] = -3.0;
+        m.invalidate_bounds();
+        assert_eq!(m.bounds(), b);
+        let mut turned = m.clone();
+        turned.rotate(Quaternion::from_axis_angle(
+            [0.0, 0.0, 1.0],
+            std::f64::consts::FRAC_PI_2,
+        ));
+        assert!(close(&turned.bounds().aabb.max, &[1.0, 3.0, 0.5]));
+        assert_eq!(m, {
+            let mut n = Mesh::new();
+            n.positions = corners.clone();
+            n
+        });
+
+        let mut e = Entity::<f64>::new();
+        e.add_model(m);
+        e.model.scale = 2.0;
+        e.model.offset = [1.0, 0.0, 0.0];
+        e.orient = Quaternion::from_axis_angle(
+            [0.0, 0.0, 1.0],
+            std::f64::consts::FRAC_PI_2,
+        );
+        e.pos.q1 = Quaternion::fom_imag([10.0, 0.0, 0.0]);
+        let w = e.bounds();
+        assert!(close(&w.aabb.min, &[8.0, -5.0, -1.0]));
+        assert!(close(&w.aabb.max, &[12.0, 7.0, 1.0]));
+        assert!(close(&w.sphere.center, &[10.0, 1.0, 0.0]));
+        assert!(close(&[w.sphere.radius], &[2.0 * 10.25f64.sqrt()]));
+        assert!(close(&w.obb.center, &[10.0, 1.0, 0.0]));
+    }
+    #[test]
+    fn baked_transform() {
+        use crate::{Mat4, Mesh, NormalMode, Quaternion, Vec3};
+        let mut m = Mesh::<f64>::new();
+        m.add_points(vec![[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+        m.indices = vec![0, 1, 2];
+        m.uvs = vec![vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]];
+        m.compute_normals(NormalMode::Smooth).compute_tangents();
+        m.scale = 2.0;
+        m.offset = [1.0, 0.0, 0.0];
+
+        let b = m.baked();
+        assert_eq!((b.scale, b.offset), (1.0, [0.0; 3]));
+        for (p, q) in m.positions.iter().zip(&b.positions) {
+            let live = m.model_matrix().transform_point(Vec3::new(*p));
+            assert!(close(&live.val, q));
+        }
+        assert_eq!(b.normals, m.normals);
+        let mut r = m.clone();
+        let quarter = Quaternion::from_axis_angle(
+            [0.0, 0.0, 1.0],
+            std::f64::consts::FRAC_PI_2,
+        );
+        r.apply_transform(Some(quarter));
+        assert!(close(&r.positions[0], &[1.0, 2.0, 0.0]));
+        assert!(close(&r.positions[2], &[1.0, 0.0, 2.0]));
+        assert!(close(&r.normals[0], &quarter.rotate(m.normals[0])));
+
+        let mut s = m.clone();
+        s.transform(Mat4::from_scale(Vec3::new([2.0, 1.0, 1.0])));
+        let mut fresh = s.clone();
+        fresh.compute_normals(NormalMode::Smooth).compute_tangents();
+        assert!(close(&s.normals[1], &fresh.normals[1]));
+        assert!(close(&s.tangents[1], &fresh.tangents[1]));
+
+        let mut flip = m.clone();
+        flip.transform(Mat4::from_scale(Vec3::new([-1.0, 1.0, 1.0])));
+        assert_eq!(flip.indices, vec![0, 2, 1]);
+        let mut fresh = flip.clone();
+        fresh.compute_normals(NormalMode::Smooth).compute_tangents();
+        assert!(close(&flip.normals[0], &fresh.normals[0]));
+        assert!(close(&flip.tangents[0], &fresh.tangents[0]));
+    }
+    #[test]
+    fn optimize() {
+        use crate::Mesh;
+        let mut quad = Mesh::<f64>::new();
+        quad.add_points(vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [1.0, 1.0, 1e-4],
+            [0.0, 1.0, 0.0],
+            [5.0, 5.0, 5.0],
+        ]);
+        quad.indices = vec![0, 1, 2, 3, 4, 5];
+        let mut exact = quad.clone();
+        exact.weld(0.0);
+        assert_eq!(exact.positions.len(), 5);
+        assert_eq!(exact.indices, vec![0, 1, 2, 0, 3, 4]);
+        let mut near = quad.clone();
+        near.weld(1e-3);
+        assert_eq!(near.positions.len(), 4);
+        assert_eq!(near.indices, vec![0, 1, 2, 0, 2, 3]);
+        let mut seam = quad.clone();
+        seam.uvs = vec![(0..7).map(|i| [(i / 3) as f64, 0.0]).collect()];
+        seam.weld(1e-3);
+        assert_eq!(seam.positions.len(), 6);
+        let mut lit = quad.clone();
+        lit.normals = (0..7).map(|i| [0.0, 0.0, i as f64]).collect();
+        lit.remove_unused();
+        assert_eq!(lit.normals.len(), 6);
+        assert_eq!(lit.normals[5], [0.0, 0.0, 5.0]);
+        // Far cells saturate instead of overflowing; NaNs never merge.
+        let mut wild = Mesh::<f64>::new();
+        wild.add_points(vec![
+            [f64::NAN, 0.0, 0.0],
+            [f64::NAN, 0.0, 0.0],
+            [-1e30, 0.0, 0.0],
+            [-1e30, 0.0, 0.0],
+            [1e30, f64::INFINITY, 0.0],
+            [f64::MAX, 0.0, 0.0],
+            [f64::MAX, 0.0, 0.0],
+        ]);
+        wild.indices = vec![0, 2, 5, 1, 3, 6, 4, 2, 5];
+        wild.weld(1e-3);
+        assert_eq!(wild.positions.len(), 5);
+        assert_eq!(wild.indices, vec![0, 2, 4, 1, 2, 4, 3, 2, 4]);
+
+        // A shuffled 24x24 grid: the reorder must keep every triangle and
+        // clearly beat the scrambled order.
+        let n = 25;
+        let mut grid = Mesh::<f64>::new();
+        grid.add_points(
+            (0..n * n)
+                .map(|i| [(i % n) as f64, (i / n) as f64, 0.0])
+                .collect(),
+        );
+        let mut tris = vec![];
+        for y in 0..n - 1 {
+            for x in 0..n - 1 {
+                let v = y * n + x;
+                tris.push([v, v + 1, v + n + 1]);
+                tris.push([v, v + n + 1, v + n]);
+            }
+        }
+        let len = tris.len();
+        for i in 0..len {
+            tris.swap(i, (i * 7919) % len);
+        }
+        grid.indices = tris.iter().flatten().copied().collect();
+        let key = |m: &Mesh<f64>| {
+            let mut t: Vec<Vec<[i64; 2]>> = m
+                .indices
+                .chunks(3)
+                .map(|t| {
+                    let mut c: Vec<[i64; 2]> = t
+                        .iter()
+                        .map(|&i| {
+                            let p = m.positions[i as usize];
+                            [p[0] as i64, p[1] as i64]
+                        })
+                        .collect();
+                    c.sort();
+                    c
+                })
+                .collect();
+            t.sort();
+            t
+        };
+        let before = key(&grid);
+        let report = grid.optimize(0.0);
+        assert_eq!(key(&grid), before);
+        assert_eq!((report.vertices_before, report.vertices_after), (625, 625));
+        assert!(report.acmr_before > 2.0);
+        assert!(report.acmr_after < 0.8, "{:?}", report);
+        assert_eq!(report.acmr_after, grid.acmr(crate::optimize::CACHE_SIZE));
+        let first_use: Vec<u32> =
+            grid.indices.iter().fold(vec![], |mut v, &i| {
+                if !v.contains(&i) {
+                    v.push(i);
+                }
+                v
+            });
+        assert_eq!(first_use, (0..625).collect::<Vec<u32>>());
+
+        let mut pieces = Mesh::<f64>::new();
+        let n = 100_000;
+        pieces.positions = (0..3 * n).map(|i| [i as f64, 0.0, 0.0]).collect();
+        pieces.indices = (0..3 * n as u32).rev().collect();
+        let start = std::time::Instant::now();
+        pieces.optimize_vertex_cache();
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+        let mut tris: Vec<&[u32]> = pieces.indices.chunks(3).collect();
+        tris.sort();
+        assert!(tris.iter().enumerate().all(|(f, t)| {
+            let f = 3 * f as u32;
+            t == &[f + 2, f + 1, f]
+        }));
+    }
+    #[test]
+    fn validate_and_repair() {
+        use crate::{Mesh, MeshReport, NormalMode};
+        let mut m = Mesh::<f64>::new();
+        m.add_points(vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [f64::NAN, 0.0, 0.0],
+            [9.0, 9.0, 9.0],
+        ]);
+        m.normals = vec![[0.0, 0.0, 1.0]; 5];
+        m.colors = vec![[1.0; 4]; 6];
+        m.uvs = vec![vec![[0.0; 2]; 6], vec![[0.0; 2]; 2]];
+        m.indices = vec![0, 1, 2, 0, 1, 3, 1, 1, 2, 0, 7, 2, 4, 1, 2, 2, 1];
+        let report = m.validate();
+        assert_eq!(report, MeshReport {
+            out_of_range:     1,
+            trailing_indices: 2,
+            non_finite:       1,
+            degenerate:       2,
+            mismatched:       vec!["normals", "uvs"],
+            unused_vertices:  1,
+        });
+        assert!(!report.is_ok());
+        assert_eq!(m.mismatched_attribute(), Some("normals"));
+
+        assert_eq!(m.repair(), report);
+        let fixed = m.validate();
+        assert!(fixed.is_ok(), "{:?}", fixed);
+        assert_eq!(fixed.unused_vertices, 2);
+        assert_eq!(m.indices, vec![0, 1, 2]);
+        assert_eq!(m.positions.len(), 5);
+        assert_eq!(m.positions[4], [9.0; 3]);
+        assert_eq!((m.uvs.len(), m.colors.len()), (1, 5));
+        assert!(close(&m.normals[0], &[0.0, 0.0, 1.0]));
+        assert_eq!(m.repair(), fixed);
+
+        // Loading repairs before deriving normals and tangents, and both
+        // skip triangles that reach past the last vertex.
+        let obj = tobj::Mesh {
+            positions:   vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+            normals:     vec![],
+            texcoords:   vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0],
+            indices:     vec![0, 1, 2, 0, 1, 5],
+            material_id: None,
+        };
+        let (loaded, report) =
+            Mesh::<f64>::from_tobj_checked(&obj, [0.0; 3], 1.0);
+        assert_eq!(report.out_of_range, 1);
+        assert_eq!(loaded.indices, vec![0, 1, 2]);
+        assert!(loaded.validate().is_ok());
+        assert_eq!(loaded.tangents, vec![[1.0, 0.0, 0.0, 1.0]; 3]);
+        for &mode in &[
+            NormalMode::Smooth,
+            NormalMode::Crease(1.0),
+            NormalMode::Flat,
+        ] {
+            let mut m = loaded.clone();
+            m.indices = obj.indices.clone();
+            m.compute_normals(mode).compute_tangents();
+            assert!(m.normals.iter().all(|n| close(n, &[0.0, 0.0, 1.0])));
+            assert_eq!(m.tangents, vec![[1.0, 0.0, 0.0, 1.0]; 3]);
+        }
+        for &mode in &[NormalMode::Crease(1.0), NormalMode::Flat] {
+            let mut m = Mesh::<f64>::plane(1.0, 1.0, [1, 1]);
+            m.uvs.push(vec![[0.0; 2]]);
+            m.colors = vec![[1.0; 4]];
+            m.tangents = vec![[1.0, 0.0, 0.0, 1.0]];
+            m.compute_normals(mode);
+            assert_eq!(m.uvs.len(), 1);
+            assert!(m.colors.is_empty() && m.tangents.is_empty());
+            assert!(m.validate().is_ok());
+        }
+    }
+    #[test]
+    fn primitives() {
+        use crate::{Mesh, Vec3};
+        use std::f64::consts::PI;
+        let v = |p: [f64; 3]| Vec3::new(p);
+        let check = |m: Mesh<f64>, volume: f64, tol: f64, closed: bool| {
+            assert!(m.validate().is_ok(), "{:?}", m.validate());
+            assert_eq!(m.validate().unused_vertices, 0);
+            assert_eq!(m.uvs.len(), 1);
+            assert_eq!(m.tangents.len(), m.positions.len());
+            let mut vol = 0.0;
+            for t in m.indices.chunks(3) {
+                let p = [0, 1, 2].map(|k| v(m.positions[t[k] as usize]));
+                let face = (p[1] - p[0]).cross(p[2] - p[0]);
+                let n = t
+                    .iter()
+                    .fold(v([0.0; 3]), |a, &i| a + v(m.normals[i as usize]));
+                assert!(face.dot(n) > 0.0, "{:?}", t);
+                vol += p[0].dot(p[1].cross(p[2])) / 6.0;
+            }
+            assert!(m
+                .normals
+                .iter()
+                .all(|&n| (v(n).length() - 1.0).abs() < 1e-9));
+            assert!(
+                (vol - volume).abs() < tol * volume.abs().max(1.0),
+                "{}",
+                vol
+            );
+            if closed {
+                let mut w = m.clone();
+                w.uvs.clear();
+                w.normals.clear();
+                w.tangents.clear();
+                w.weld(0.0);
+                let mut edges = std::collections::HashMap::new();
+                for t in w.indices.chunks(3) {
+                    for k in 0..3 {
+                        let (a, b) = (t[k], t[(k + 1) % 3]);
+                        *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+                    }
+                }
+                assert!(edges.values().all(|&c| c == 2));
+            }
+            m
+        };
+
+        let plane = check(Mesh::plane(2.0, 4.0, [2, 3]), 0.0, 1e-9, false);
+        assert_eq!((plane.positions.len(), plane.indices.len()), (12, 36));
+        assert!(plane.normals.iter().all(|n| close(n, &[0.0, 1.0, 0.0])));
+        let b = plane.bounds().aabb;
+        assert!(
+            close(&b.min, &[-1.0, 0.0, -2.0]) && close(&b.max, &[1.0, 0.0, 2.0])
+        );
+        // Grid steps are computed in the mesh's precision.
+        let thirds = Mesh::<f64>::plane(3.0, 1.0, [3, 1]);
+        assert!(thirds.uvs[0].iter().any(|uv| uv[0] == 1.0 / 3.0));
+
+        let cube = check(Mesh::cuboid([1.0, 2.0, 3.0], 2), 6.0, 1e-9, true);
+        assert_eq!((cube.positions.len(), cube.indices.len()), (54, 144));
+        assert!(close(&cube.bounds().aabb.max, &[0.5, 1.0, 1.5]));
+
+        let sphere =
+            check(Mesh::uv_sphere(2.0, 64, 32), 32.0 * PI / 3.0, 0.01, true);
+        assert_eq!(sphere.positions.len(), 65 * 33 - 2);
+        assert_eq!(sphere.indices.len(), 3 * (64 * 32 * 2 - 2 * 64));
+        assert!(sphere
+            .positions
+            .iter()
+            .all(|&p| (v(p).length() - 2.0).abs() < 1e-9));
+
+        let ico = check(Mesh::icosphere(1.0, 3), 4.0 * PI / 3.0, 0.02, true);
+        assert_eq!(ico.indices.len(), 3 * 20 * 64);
+        assert!(ico.uvs[0].iter().all(|uv| uv[0] >= 0.0 && uv[0] < 1.5));
+
+        check(Mesh::cylinder(1.0, 2.0, 64, 2), 2.0 * PI, 0.01, true);
+        check(Mesh::cone(1.0, 3.0, 64, 4), PI, 0.01, true);
+        check(
+            Mesh::torus(2.0, 0.5, 64, 32),
+            2.0 * PI * PI * 2.0 * 0.25,
+            0.01,
+            true,
+        );
+        let capsule = check(
+            Mesh::capsule(0.5, 1.0, 48, 12),
+            PI * 0.25 + 4.0 / 3.0 * PI * 0.125,
+            0.01,
+            true,
+        );
+        assert!(close(&capsule.bounds().aabb.max[1..2], &[1.0]));
+        assert!(capsule.uvs[0].iter().all(|uv| (0.0..=1.0).contains(&uv[1])));
+    }
+    #[test]
+    fn simplify() {
+        use crate::{Aabb, Mesh};
+        let used = |m: &Mesh<f64>, indices: &[u32]| -> Vec<[f64; 3]> {
+            indices.iter().map(|&i| m.positions[i as usize]).collect()
+        };
+
+        // Flat with a straight border: everything but the corners goes at
+        // no cost, and the corners may not move at all.
+        let mut plane = Mesh::<f64>::plane(2.0, 2.0, [8, 8]);
+        assert_eq!(plane.simplify(0, 1e-6), 0.0);
+        assert_eq!(plane.indices.len(), 6);
+        let b = Aabb::from_points(&used(&plane, &plane.indices));
+        assert!(
+            close(&b.min, &[-1.0, 0.0, -1.0]) && close(&b.max, &[1.0, 0.0, 1.0])
+        );
+        assert!(plane.validate().is_ok());
+        let mut stray = Mesh::<f64>::plane(1.0, 1.0, [1, 1]);
+        stray.indices = vec![0, 1, 2, 0, 2, 9];
+        assert_eq!(stray.simplify(0, 1.0), 0.0);
+        assert_eq!(stray.indices, vec![0, 1, 2]);
+
+        let mut sphere = Mesh::<f64>::uv_sphere(1.0, 32, 16);
+        let full = sphere.indices.len() / 3;
+        let mut coarse = sphere.clone();
+        let error = coarse.simplify(full / 4, 1.0);
+        let tris = coarse.indices.len() / 3;
+        assert!(tris <= full / 4 && tris > 16, "{}", tris);
+        assert!(error > 0.0 && error < 0.3, "{}", error);
+        assert!(coarse.validate().is_ok());
+        // UVs still wrap the right way and the shell stays closed.
+        for t in coarse.indices.chunks(3) {
+            let u: Vec<f64> =
+                t.iter().map(|&i| coarse.uvs[0][i as usize][0]).collect();
+            let span = u.iter().fold(0.0f64, |a, &x| a.max((x - u[0]).abs()));
+            assert!(span < 0.5, "{:?}", u);
+        }
+        let mut shell = coarse.clone();
+        shell.uvs.clear();
+        shell.normals.clear();
+        shell.tangents.clear();
+        shell.weld(0.0);
+        let mut edges = std::collections::HashMap::new();
+        for t in shell.indices.chunks(3) {
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+            }
+        }
+        assert!(edges.values().all(|&c| c == 2));
+        // A tight bound stops early.
+        assert!(sphere.clone().simplify(0, 1e-3) <= 1e-3);
+
+        let lods = sphere.generate_lods(4, 0.5, 1.0).to_vec();
+        assert!(lods.len() >= 3);
+        assert_eq!(sphere.lod(0), &sphere.indices[..]);
+        assert_eq!(sphere.lod(99), &lods.last().unwrap().indices[..]);
+        for w in lods.windows(2) {
+            assert!(w[1].indices.len() < w[0].indices.len());
+            assert!(w[1].error >= w[0].error);
+        }
+        let n = sphere.positions.len();
+        sphere.remove_unused();
+        assert_eq!(sphere.positions.len(), n);
+        assert_eq!(sphere.lods, lods);
+        sphere.weld(0.0);
+        assert!(sphere.lods.is_empty());
+    }
+    #[test]
+    fn axis_angle() {
+        use crate::Quaternion;
+        let q = Quaternion::from_axis_angle([0.0, 0.0, 2.0], 0.5f64);
+        let (axis, angle) = q.to_axis_angle();
+        assert!(close(&axis, &[0.0, 0.0, 1.0]));
+        assert!((angle - 0.5).abs() < 1e-12);
+        let half_pi = std::f64::consts::FRAC_PI_2;
+        let r = Quaternion::from_axis_angle([0.0, 0.0, 1.0], half_pi);
+        assert!(close(&r.rotate([1.0, 0.0, 0.0]), &[0.0, 1.0, 0.0]));
+        let (axis, angle) = Quaternion::<f64>::identity().to_axis_angle();
+        assert_eq!((axis, angle), ([1.0, 0.0, 0.0], 0.0));
+    }
+    #[test]
+    fn euler() {
+        use crate::{EulerOrder, Quaternion};
+        let angles = [0.3f64, 0.7, -1.1];
+        for &order in EulerOrder::ALL.iter() {
+            let q = Quaternion::from_euler(order, angles);
+            let back = q.to_euler(order);
+            assert!(close(&back, &angles), "{:?} {:?}", order, back);
+            assert!(same_rot(Quaternion::from_euler(order, back), q));
+        }
+        let q = Quaternion::from_euler(EulerOrder::XYZ, [0.0f64, 0.0, 0.5]);
+        assert!(same_rot(
+            q,
+            Quaternion::from_axis_angle([0.0, 0.0, 1.0], 0.5)
+        ));
+        // gimbal lock still reproduces the rotation
+        let half_pi = std::f64::consts::FRAC_PI_2;
+        for &order in EulerOrder::ALL.iter() {
+            let middle = if order.axes()[0] == order.axes()[2] {
+                0.0
+            } else {
+                half_pi
+            };
+            let q = Quaternion::from_euler(order, [0.3, middle, 0.2]);
+            let back = q.to_euler(order);
+            assert!(same_rot(Quaternion::from_euler(order, back), q));
+        }
+    }
+    #[test]
+    fn matrices() {
+        use crate::Quaternion;
+        let q = Quaternion::new([0.3f64, -0.5, 0.7, 0.1]).u();
+        let m = q.to_mat3();
+        let v = [0.2, -1.3, 0.8];
+        let mv: Vec<f64> = (0..3)
+            .map(|r| m[0][r] * v[0] + m[1][r] * v[1] + m[2][r] * v[2])
+            .collect();
+        assert!(close(&mv, &q.rotate(v)));
+        assert!(same_rot(Quaternion::from_mat3(m), q));
+        assert!(same_rot(Quaternion::from_mat4(q.to_mat4()), q));
+        for &w in [0.0f64, 0.01, -0.9].iter() {
+            let q = Quaternion::new([w, 0.6, -0.2, 0.4]).u();
+            assert!(same_rot(Quaternion::from_mat3(q.to_mat3()), q));
+            let q = Quaternion::new([w, 0.1, -0.8, 0.4]).u();
+            assert!(same_rot(Quaternion::from_mat3(q.to_mat3()), q));
+            let q = Quaternion::new([w, 0.1, 0.2, -0.9]).u();
+            assert!(same_rot(Quaternion::from_mat3(q.to_mat3()), q));
+        }
+        assert_eq!(q.to_mat4()[3], [0.0, 0.0, 0.0, 1.0]);
+    }
+    #[test]
+    fn interpolation() {
+        use crate::Quaternion;
+        let z = [0.0, 0.0, 1.0];
+        let q0 = Quaternion::from_axis_angle(z, 0.0f64);
+        let q1 = Quaternion::from_axis_angle(z, 1.0);
+        let mid = Quaternion::from_axis_angle(z, 0.5);
+        assert!(same_rot(q0.slerp(q1, 0.0), q0));
+        assert!(same_rot(q0.slerp(q1, 1.0), q1));
+        assert!(same_rot(q0.slerp(q1, 0.5), mid));
+        // the antipodal representation takes the same short path
+        assert!(same_rot(q0.slerp(-q1, 0.5), mid));
+        assert!(same_rot(q0.nlerp(-q1, 0.5), mid));
+        let near = Quaternion::from_axis_angle(z, 1e-5);
+        assert!((q0.slerp(near, 0.3).n() - 1.0).abs() < 1e-12);
+        assert!((q0.slerp(q0, 0.3).n() - 1.0).abs() < 1e-12);
+
+        // evenly spaced keys about one axis make SQUAD reduce to slerp
+        let q2 = Quaternion::from_axis_angle(z, 2.0);
+        let a = Quaternion::squad_tangent(q0, q1, q2);
+        assert!(same_rot(a, q1));
+        let x = Quaternion::from_axis_angle([1.0, 0.0, 0.0], 0.7);
+        let b = Quaternion::squad_tangent(q1, q2, x);
+        for &t in [0.0, 0.25, 0.5, 1.0].iter() {
+            let s = q1.squad(q1, q2, q2, t);
+            assert!(same_rot(s, q1.slerp(q2, t)));
+            assert!((q1.squad(a, b, q2, t).n() - 1.0).abs() < 1e-12);
+        }
+        assert!(same_rot(q1.squad(a, b, q2, 1.0), q2));
+    }
+    #[test]
+    fn inverse() {
+        use crate::Quaternion;
+        use num_traits::Zero;
+        let q = Quaternion::new([1.0f64, -2.0, 0.5, 3.0]);
+        let p = Quaternion::new([0.2f64, 0.4, -1.0, 2.0]);
+        assert!(close(&(q * q.inverse()).val, &[1.0, 0.0, 0.0, 0.0]));
+        assert!(close(&(q.inverse() * q).val, &[1.0, 0.0, 0.0, 0.0]));
+        assert!(close(&((q / p) * p).val, &q.val));
+        let mut r = q;
+        r /= p;
+        assert_eq!(r, q / p);
+        r.inverse_mut();
+        assert!(close(&r.val, &(p / q).val));
+        assert_eq!(Quaternion::<f64>::zero().checked_inverse(), None);
+        assert_eq!(q.checked_inverse(), Some(q.inverse()));
+
+        let rot = Quaternion::from_axis_angle([1.0, 1.0, 0.0], 0.8f64);
+        let v = [0.3, -0.2, 1.5];
+        assert!(close(&rot.inverse().rotate(rot.rotate(v)), &v));
+    }
+    #[test]
+    fn exp_ln() {
+        use crate::Quaternion;
+        let e = std::f64::consts::E;
+        let half_pi = std::f64::consts::FRAC_PI_2;
+        let one = Quaternion::new([1.0f64, 0.0, 0.0, 0.0]);
+        assert!(close(&one.exp().val, &[e, 0.0, 0.0, 0.0]));
+        let i = Quaternion::fom_imag([half_pi, 0.0, 0.0]);
+        assert!(close(&i.exp().val, &[0.0, 1.0, 0.0, 0.0]));
+        assert!(close(&Quaternion::new([0.0, 0.0, 1.0, 0.0]).ln().val, &[
+            0.0, 0.0, half_pi, 0.0
+        ]));
+        assert!(close(
+            &Quaternion::new([-2.0f64, 0.0, 0.0, 0.0]).ln().exp().val,
+            &[-2.0, 0.0, 0.0, 0.0]
+        ));
+
+        let q = Quaternion::new([0.5f64, -1.0, 0.25, 2.0]);
+        assert!(close(&q.ln().exp().val, &q.val));
+        assert!(close(&q.powf(2.0).val, &(q * q).val));
+        assert!(close(&q.powf(1.0).val, &q.val));
+        let z = [0.0, 0.0, 1.0];
+        let r = Quaternion::from_axis_angle(z, 1.2f64);
+        assert!(close(
+            &r.powf(0.5).val,
+            &Quaternion::from_axis_angle(z, 0.6).val
+        ));
+        assert!(close(&r.powf(-1.0).val, &r.inverse().val));
+
+        // Small but far from real: the angle must not be lost to `epsilon`.
+        let tiny = Quaternion::new([0.0f32, 1e-8, 0.0, 0.0]);
+        let l = tiny.ln();
+        assert!((l.val[1] - half_pi as f32).abs() < 1e-6, "{:?}", l);
+        let sq = tiny.powf(2.0).val[0];
+        assert!((sq / (tiny * tiny).val[0] - 1.0).abs() < 1e-4, "{}", sq);
+        // Nearly real: the vector part is `v / w`.
+        let near = Quaternion::new([2.0f64, 1e-20, 0.0, 0.0]).ln();
+        assert!((near.val[1] - 5e-21).abs() < 1e-30);
+    }
+    #[test]
+    fn integrate() {
+        use crate::Quaternion;
+        let w = [0.0, 0.0, 2.0];
+        let expected = Quaternion::from_axis_angle([0.0, 0.0, 1.0], 1.0f64);
+        let q = Quaternion::identity().integrate(w, 0.5);
+        assert!(same_rot(q, expected));
+        let mut q = Quaternion::identity();
+        for _ in 0..100 {
+            q = q.integrate(w, 0.005);
+        }
+        assert!(same_rot(q, expected));
+        // constant velocity about a fixed axis composes exactly
+        let w = [0.3, -0.4, 1.2];
+        let a = Quaternion::identity()
+            .integrate(w, 0.2f64)
+            .integrate(w, 0.3);
+        assert!(same_rot(a, Quaternion::identity().integrate(w, 0.5)));
+    }
+    #[test]
+    fn dual_quaternion() {
+        use crate::{DualQuaternion, Octonion, Quaternion};
+        let r1 = Quaternion::from_axis_angle([0.0, 0.0, 1.0], 0.9f64);
+        let r2 = Quaternion::from_axis_angle([1.0, -1.0, 0.5], -0.4f64);
+        let a = DualQuaternion::from_rotation_translation(r1, [1.0, 2.0, 3.0]);
+        let b = DualQuaternion::from_rotation_translation(r2, [-0.5, 0.0, 4.0]);
+        assert!(close(&a.translation(), &[1.0, 2.0, 3.0]));
+        assert!(same_rot(a.rotation(), r1));
+
+        let p = [0.3, -1.2, 2.0];
+        let rp = r1.rotate(p);
+        assert!(close(&a.transform_point(p), &[
+            rp[0] + 1.0,
+            rp[1] + 2.0,
+            rp[2] + 3.0
+        ]));
+        assert!(close(
+            &(a * b).transform_point(p),
+            &a.transform_point(b.transform_point(p))
+        ));
+        assert!(close(
+            &a.inverse().transform_point(a.transform_point(p)),
+            &p
+        ));
+        let m = a.to_mat4();
+        let mp: Vec<f64> = (0..3)
+            .map(|r| m[0][r] * p[0] + m[1][r] * p[1] + m[2][r] * p[2] + m[3][r])
+            .collect();
+        assert!(close(&mp, &a.transform_point(p)));
+
+        let o: Octonion<f64> = a.into();
+        assert_eq!(DualQuaternion::from(o), a);
+    }
+    #[test]
+    fn dual_quaternion_blending() {
+        use crate::{DualQuaternion, Quaternion};
+        let z = [0.0, 0.0, 1.0];
+        let start = DualQuaternion::from_translation([0.0f64, 0.0, 0.0]);
+        let end = DualQuaternion::from_translation([2.0, -4.0, 0.0]);
+        assert!(close(&start.sclerp(end, 0.25).translation(), &[
+            0.5, -1.0, 0.0
+        ]));
+
+        // a screw about Z: half the turn and half the climb
+        let a = DualQuaternion::from_rotation_translation(
+            Quaternion::from_axis_angle(z, 0.0f64),
+            [1.0, 0.0, 0.0],
+        );
+        let b = DualQuaternion::from_rotation_translation(
+            Quaternion::from_axis_angle(z, 1.0),
+            [1.0f64.cos(), 1.0f64.sin(), 2.0],
+        );
+        let mid = a.sclerp(b, 0.5);
+        assert!(same_rot(
+            mid.rotation(),
+            Quaternion::from_axis_angle(z, 0.5)
+        ));
+        assert!(close(&mid.transform_point([0.0; 3]), &[
+            0.5f64.cos(),
+            0.5f64.sin(),
+            1.0
+        ]));
+        assert!(close(
+            &a.sclerp(b, 1.0).transform_point([0.0; 3]),
+            &b.transform_point([0.0; 3])
+        ));
+        assert!(close(
+            &a.sclerp(-b, 1.0).transform_point([0.0; 3]),
+            &b.transform_point([0.0; 3])
+        ));
+
+        let blended = DualQuaternion::dlb(&[(0.5, a), (0.5, -a)]);
+        assert!(close(&blended.transform_point([0.0; 3]), &[1.0, 0.0, 0.0]));
+        let blended = DualQuaternion::dlb(&[(0.5, start), (0.5, end)]);
+        assert!(close(&blended.translation(), &[1.0, -2.0, 0.0]));
+    }
+    #[test]
+    fn octonion_algebra() {
+        use crate::Octonion;
+        let x = Octonion::new([0.5f64, -1.0, 2.0, 0.3, -0.7, 1.1, 0.2, -0.4]);
+        let y = Octonion::new([1.5f64, 0.6, -0.2, 0.9, 0.4, -1.3, 0.8, 0.1]);
+        let z = Octonion::new([-0.3f64, 0.7, 1.2, -0.6, 0.5, 0.2, -1.0, 0.9]);
+        let eq = |a: Octonion<f64>, b: Octonion<f64>| {
+            close(&a.as_array(), &b.as_array())
+        };
+        let zero = Octonion::new([0.0; 8]);
+
+        assert!((x.norm() * y.norm() - (x * y).norm()).abs() < 1e-9);
+        assert!((x.u().norm() - 1.0).abs() < 1e-12);
+        assert!(eq(x * x.inverse(), Octonion::identity()));
+        assert!(eq(x.inverse() * x, Octonion::identity()));
+        assert!(eq((x / y) * y, x));
+        assert!(eq(-(-x), x));
+        assert!(eq(x + -x, zero));
+        assert_eq!(zero.checked_inverse(), None);
+
+        // alternative and flexible, but not associative or commutative
+        assert!(eq(Octonion::associator(x, x, y), zero));
+        assert!(eq(Octonion::associator(x, y, y), zero));
+        assert!(eq(Octonion::associator(x, y, x), zero));
+        assert!(!eq(Octonion::associator(x, y, z), zero));
+        assert!(!eq(Octonion::commutator(x, y), zero));
+
+        // Moufang identities
+        assert!(eq(z * (x * (z * y)), ((z * x) * z) * y));
+        assert!(eq(x * (z * (y * z)), ((x * z) * y) * z));
+        assert!(eq((z * x) * (y * z), (z * (x * y)) * z));
+        assert!(eq((z * x) * (y * z), z * ((x * y) * z)));
+    }
+    #[test]
+    fn cayley_dickson() {
+        use crate::{
+            CayleyDickson,
+            Complex,
+            Hypercomplex,
+            Octonion,
+            Quaternion,
+            Sedenion,
+        };
+        let i = Complex::new(0.0f64, 1.0);
+        assert_eq!(i * i, Complex::new(-1.0, 0.0));
+
+        // doubling the complex numbers reproduces Hamilton's product
+        let a = Quaternion::new([1.0f64, 2.0, 3.0, 6.0]);
+        let b = Quaternion::new([0.5f64, -1.0, 0.25, 2.0]);
+        let ca: CayleyDickson<Complex<f64>> = a.into();
+        let cb: CayleyDickson<Complex<f64>> = b.into();
+        assert_eq!(Quaternion::from(ca * cb), a * b);
+        assert_eq!(Quaternion::from(ca.conj()), a.conj());
+
+        let o = Octonion::new([2.0, 4.0, 6.0, 8.0, 10.0, 12.0, 14.0, 16.0]);
+        let co = CayleyDickson::from(o);
+        assert_eq!(co.to_vec(), o.as_array().to_vec());
+        assert_eq!(CayleyDickson::<Quaternion<f64>>::DIM, 8);
+
+        let x: Sedenion<f64> = CayleyDickson::from_slice(&[
+            0.5, -1.0, 2.0, 0.3, -0.7, 1.1, 0.2, -0.4, 1.5, 0.6, -0.2, 0.9, 0.4,
+            -1.3, 0.8, 0.1,
+        ]);
+        let y: Sedenion<f64> = CayleyDickson::from_slice(&[
+            -0.3, 0.7, 1.2, -0.6, 0.5, 0.2, -1.0, 0.9, 0.1, -0.8, 0.4, 0.3, -0.2,
+            1.4, -0.5, 0.6,
+        ]);
+        assert_eq!(Sedenion::<f64>::DIM, 16);
+        // conjugation reverses products and x x* is real in every doubling
+        assert!(close(
+            &(x * y).conj().to_vec(),
+            &(y.conj() * x.conj()).to_vec()
+        ));
+        let mut nx = vec![0.0; 16];
+        nx[0] = x.norm_sqr();
+        assert!(close(&(x * x.conj()).to_vec(), &nx));
+        // sedenions are flexible but lose alternativity
+        assert!(close(&((x * y) * x).to_vec(), &(x * (y * x)).to_vec()));
+        assert!(!close(&((x * x) * y).to_vec(), &(x * (x * y)).to_vec()));
+        assert!(close(
+            &(x * x.inverse()).to_vec(),
+            &Sedenion::identity().to_vec()
+        ));
+    }
+    #[test]
+    fn vectors() {
+        use crate::{Quaternion, Vec2, Vec3, Vec4};
+        let x = Vec3::new([1.0f64, 0.0, 0.0]);
+        let y = Vec3::new([0.0f64, 1.0, 0.0]);
+        assert_eq!(x.cross(y), Vec3::new([0.0, 0.0, 1.0]));
+        assert_eq!(x.dot(y), 0.0);
+        assert_eq!(Vec2::new([3.0f64, 4.0]).length(), 5.0);
+        assert!(
+            (Vec4::new([1.0f64, 2.0, -3.0, 4.0]).normalize().length() - 1.0)
+                .abs() <
+                1e-12
+        );
+        assert_eq!((x + y) * 2.0 - y, Vec3::new([2.0, 1.0, 0.0]));
+        let q: Quaternion<f64> = Vec3::new([1.0, 2.0, 3.0]).into();
+        assert_eq!(q, Quaternion::fom_imag([1.0, 2.0, 3.0]));
+        assert_eq!(Vec3::from(q), Vec3::new(q.imag()));
+    }
+    #[test]
+    fn matrices_and_projection() {
+        use crate::{Mat3, Mat4, Quaternion, Vec3};
+        let q = Quaternion::from_axis_angle([1.0, 2.0, -0.5], 0.7f64);
+        let v = Vec3::new([0.3, -1.0, 2.0]);
+        let m3 = Mat3::from(q);
+        assert!(close(&(m3 * v).val, &q.rotate(v.val)));
+        assert!((m3.determinant() - 1.0).abs() < 1e-12);
+        assert!(close(&(m3 * m3.inverse().unwrap() * v).val, &v.val));
+        assert!(same_rot(m3.to_quaternion(), q));
+        assert_eq!(Mat3::<f64>::from_cols(v, v, v).inverse(), None);
+
+        let m = Mat4::from_translation(Vec3::new([1.0, 2.0, 3.0])) *
+            Mat4::from_rotation(q) *
+            Mat4::from_scale(Vec3::new([2.0, 2.0, 2.0]));
+        let p = m.transform_point(v);
+        assert!(close(&m.inverse().unwrap().transform_point(p).val, &v.val));
+        assert!(close(&m.transform_vector(v).val, &(m3 * v * 2.0).val));
+
+        let eye = Vec3::new([3.0f64, 2.0, 5.0]);
+        let target = Vec3::new([0.0, 0.5, -1.0]);
+        let view = Mat4::look_at(eye, target, Vec3::new([0.0, 1.0, 0.0]));
+        assert!(close(&view.transform_point(eye).val, &[0.0; 3]));
+        let t = view.transform_point(target);
+        assert!(close(&t.val, &[0.0, 0.0, -(target - eye).length()]));
+
+        let proj = Mat4::perspective(1.2f64, 1.5, 0.1, 100.0);
+        assert!(close(
+            &[proj.transform_point(Vec3::new([0.0, 0.0, -0.1]))[2]],
+            &[-1.0]
+        ));
+        assert!(close(
+            &[proj.transform_point(Vec3::new([0.0, 0.0, -100.0]))[2]],
+            &[1.0]
+        ));
+        let top = (0.6f64).tan() * 2.0;
+        assert!(close(
+            &proj.transform_point(Vec3::new([0.0, top, -2.0])).val[0..2],
+            &[0.0, 1.0]
+        ));
+
+        let ortho = Mat4::orthographic(-2.0f64, 2.0, -1.0, 1.0, 0.5, 10.0);
+        assert!(close(
+            &ortho.transform_point(Vec3::new([2.0, -1.0, -0.5])).val,
+            &[1.0, -1.0, -1.0]
+        ));
+        assert!(close(
+            &ortho.transform_point(Vec3::new([-2.0, 1.0, -10.0])).val,
+            &[-1.0, 1.0, 1.0]
+        ));
+    }
+    #[test]
+    fn batch_rotate() {
+        use crate::{Mesh, Quaternion, Soa3};
+        let q = Quaternion::from_axis_angle([0.3, -1.0, 0.6], 1.1f64);
+        let pts: Vec<[f64; 3]> = (0..19)
+            .map(|i| {
+                let i = i as f64;
+                [i.sin() * 3.0, i.cos() - 1.0, i * 0.25]
+            })
+            .collect();
+        let mut m = Mesh::new();
+        m.add_points(pts.clone());
+        m.normals = pts.clone();
+        m.rotate(q);
+        for (p, r) in pts.iter().zip(m.positions.iter()) {
+            assert!(close(r, &q.rotate(*p)));
+        }
+        assert_eq!(m.positions, m.normals);
+
+        let q32 = Quaternion::from_axis_angle([0.3, -1.0, 0.6], 1.1f32);
+        let pts32: Vec<[f32; 3]> = pts
+            .iter()
+            .map(|p| [p[0] as f32, p[1] as f32, p[2] as f32])
+            .collect();
+        let mut m32 = Mesh::new();
+        m32.add_points(pts32.clone());
+        m32.rotate(q32);
+        for (a, b) in m32.positions.iter().zip(m.positions.iter()) {
+            for k in 0..3 {
+                assert!((a[k] as f64 - b[k]).abs() < 1e-5);
+            }
+        }
+        // 19 points: four SSE steps and a scalar tail.
+        let mut batched = Soa3::from_points(&pts32);
+        batched.rotate(q32);
+        assert_eq!(batched.len(), pts.len());
+        for (a, b) in batched.to_points().iter().zip(m32.positions.iter()) {
+            assert!(a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-5));
+        }
+
+        let mut soa = Soa3::from_points(&pts);
+        assert_eq!(soa.to_points(), pts);
+        soa.rotate(q);
+        assert_eq!(soa.to_points(), m.positions);
+    }
+    #[test]
+    fn arcs_and_look() {
+        use crate::{Mat4, Quaternion, Vec3};
+        let unit = |v: [f64; 3]| {
+            let l = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+            [v[0] / l, v[1] / l, v[2] / l]
+        };
+        let pairs = [
+            ([1.0, 2.0, 3.0], [-2.0, 0.5, 1.0]),
+            ([1.0, 0.0, 0.0], [-3.0, 0.0, 0.0]),
+            ([0.0, 0.0, 2.0], [0.0, 0.0, 1.0]),
+            ([0.0, 1.0, 1.0], [0.0, -1.0, -1.0]),
+        ];
+        for &(a, b) in pairs.iter() {
+            let q = Quaternion::from_rotation_arc(a, b);
+            assert!(close(&q.rotate(unit(a)), &unit(b)), "{:?} {:?}", a, b);
+            assert!((q.n() - 1.0).abs() < 1e-12);
+        }
+        // Almost opposite is still a proper arc, not a snap to `pi`.
+        let near = [[-1.0f32, 1e-4, 0.0], [-0.6, -0.8, 1e-5], [0.0, -1.0, -3e-6]];
+        for (&a, &b) in [[1.0f32, 0.0, 0.0], [0.6, 0.8, 0.0], [0.0, 1.0, 0.0]]
+            .iter()
+            .zip(near.iter())
+        {
+            let r = Quaternion::from_rotation_arc(a, b).rotate(a);
+            let l = (b[0] * b[0] + b[1] * b[1] + b[2] * b[2]).sqrt();
+            for k in 0..3 {
+                assert!((r[k] - b[k] / l).abs() < 1e-6, "{:?} {:?}", r, b);
+            }
+        }
+
+        let forward = [1.0f64, -0.5, 2.0];
+        let up = [0.0, 1.0, 0.0];
+        let q = Quaternion::look_rotation(forward, up);
+        assert!(close(&q.rotate([0.0, 0.0, -1.0]), &unit(forward)));
+        assert!(q.rotate([0.0, 1.0, 0.0])[1] > 0.0);
+        let eye = Vec3::new([0.5, 1.0, -2.0]);
+        let view = Mat4::look_at(eye, eye + Vec3::new(forward), Vec3::new(up));
+        assert!(same_rot(view.truncate().to_quaternion(), q.conj()));
+        let q = Quaternion::look_rotation([0.0, 3.0, 0.0], up);
+        assert!(close(&q.rotate([0.0, 0.0, -1.0]), &[0.0, 1.0, 0.0]));
+
+        let q =
+            Quaternion::from_euler(crate::EulerOrder::XYZ, [0.4, -0.9, 1.3f64]);
+        let axis = [0.0, 1.0, 1.0];
+        let (swing, twist) = q.swing_twist(axis);
+        assert!(close(&(swing * twist).val, &q.val));
+        let (t_axis, _) = twist.to_axis_angle();
+        assert!(
+            close(&t_axis, &unit(axis)) ||
+                close(&t_axis, &unit([0.0, -1.0, -1.0]))
+        );
+        assert!(
+            swing
+                .imag()
+                .iter()
+                .zip(unit(axis).iter())
+                .map(|(a, b)| a * b)
+                .sum::<f64>()
+                .abs() <
+                1e-12
+        );
+        let (swing, twist) = Quaternion::from_axis_angle([1.0, 0.0, 0.0], 1.0f64)
+            .swing_twist([0.0, 1.0, 0.0]);
+        assert_eq!(twist, Quaternion::identity());
+        assert!(same_rot(
+            swing,
+            Quaternion::from_axis_angle([1.0, 0.0, 0.0], 1.0)
+        ));
+    }
+    #[test]
+    fn approx_eq() {
+        use crate::{ApproxEq, Entity, Mesh, Octonion, Quaternion};
+        assert!(1.0f64.abs_diff_eq(&1.05, 0.1));
+        assert!(!1.0f64.abs_diff_eq(&1.2, 0.1));
+        assert!(1000.0f64.relative_eq(&1001.0, 1e-9, 1e-3));
+        assert!(!1000.0f64.relative_eq(&1002.0, 1e-9, 1e-3));
+        assert!(!f64::INFINITY.relative_eq(&1e308, 1.0, 1.0));
+        let one = 1.0f32;
+        let next = f32::from_bits(one.to_bits() + 2);
+        assert!(one.ulps_eq(&next, 0.0, 2));
+        assert!(!one.ulps_eq(&next, 0.0, 1));
+        assert!(!one.ulps_eq(&-one, 0.0, u32::MAX));
+        assert!(0.0f32.ulps_eq(&-0.0, 0.0, 0));
+
+        let q = Quaternion::new([0.5f64, -0.5, 0.5, 0.5]);
+        assert!(q.abs_diff_eq(&(q + 1e-12), 1e-9));
+        assert!(!q.abs_diff_eq(&-q, 1e-9));
+        assert!(q.rotation_eq(&-q, 1e-9));
+        assert!(!q.rotation_eq(&q.conj(), 1e-9));
+        let o: Octonion<f64> = (q, q).into();
+        assert!(o.relative_eq(&(o * (1.0 + 1e-12)), 0.0, 1e-9));
+
+        let mut m = Mesh::<f64>::new();
+        m.add_points(vec![[1.0, 2.0, 3.0], [0.0, -1.0, 0.5]]);
+        let mut m2 = m.clone();
+        m2.positions[1][2] += 1e-12;
+        assert!(m.abs_diff_eq(&m2, 1e-9));
+        m2.positions.pop();
+        assert!(!m.abs_diff_eq(&m2, 1e-9));
+
+        let mut e = Entity::<f64>::new();
+        e.add_model(m);
+        let mut e2 = e.clone();
+        e2.orient = e2.orient + 1e-12;
+        assert!(e.ulps_eq(&e2, 1e-9, 4));
+        assert!(!e.abs_diff_eq(&e2, 0.0));
+    }
+}
+
+#[cfg(test)]
+mod properties {
+    use crate::{ApproxEq, Mesh, Octonion, Quaternion};
+    use proptest::prelude::*;
+
+    fn quat64() -> impl Strategy<Value = Quaternion<f64>> {
+        prop::array::uniform4(-10.0f64..10.0).prop_map(Quaternion::new)
+    }
+    fn quat32() -> impl Strategy<Value = Quaternion<f32>> {
+        prop::array::uniform4(-10.0f32..10.0).prop_map(Quaternion::new)
+    }
+    fn rotor64() -> impl Strategy<Value = Quaternion<f64>> {
+        quat64()
+            .prop_filter("non-zero", |q| q.n() > 1e-3)
+            .prop_map(|q| q.u())
+    }
+    fn rotor32() -> impl Strategy<Value = Quaternion<f32>> {
+        quat32()
+            .prop_filter("non-zero", |q| q.n() > 1e-2)
+            .prop_map(|q| q.u())
+    }
+    fn oct64() -> impl Strategy<Value = Octonion<f64>> {
+        prop::array::uniform8(-10.0f64..10.0).prop_map(Octonion::new)
+    }
+    fn points64() -> impl Strategy<Value = Vec<[f64; 3]>> {
+        prop::collection::vec(prop::array::uniform3(-100.0f64..100.0), 0..40)
+    }
+    fn points32() -> impl Strategy<Value = Vec<[f32; 3]>> {
+        prop::collection::vec(prop::array::uniform3(-100.0f32..100.0), 0..40)
+    }
+    fn length<T: num_traits::Float>(p: &[T; 3]) -> T {
+        (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt()
+    }
+
+    proptest! {
+        #[test]
+        fn norm_is_multiplicative(p in quat64(), q in quat64()) {
+            let n = p.n() * q.n();
+            prop_assert!((p * q).n().relative_eq(&n, 1e-9, 1e-12));
+        }
+
+        #[test]
+        fn norm_is_multiplicative_f32(p in quat32(), q in quat32()) {
+            let n = p.n() * q.n();
+            prop_assert!((p * q).n().relative_eq(&n, 1e-3, 1e-5));
+        }
+
+        #[test]
+        fn conj_reverses_products(p in quat64(), q in quat64()) {
+            let lhs = (p * q).conj();
+            prop_assert!(lhs.abs_diff_eq(&(q.conj() * p.conj()), 1e-9));
+        }
+
+        #[test]
+        fn inverse_undoes_product(p in quat64(), q in rotor64()) {
+            prop_assert!((p * q * q.inverse()).abs_diff_eq(&p, 1e-9));
+        }
+
+        #[test]
+        fn to_vec_round_trips(q in quat32()) {
+            prop_assert_eq!(Quaternion::from_slice(&q.to_vec()), q);
+        }
+
+        #[test]
+        fn mesh_rotate_preserves_length(q in rotor64(), pts in points64()) {
+            let mut m = Mesh::new();
+            m.add_points(pts.clone());
+            m.rotate(q);
+            for (a, b) in pts.iter().zip(m.positions.iter()) {
+                prop_assert!(length(a).abs_diff_eq(&length(b), 1e-9));
+            }
+        }
+
+        #[test]
+        fn mesh_rotate_preserves_length_f32(q in rotor32(), pts in points32()) {
+            let mut m = Mesh::new();
+            m.add_points(pts.clone());
+            m.rotate(q);
+            for (a, b) in pts.iter().zip(m.positions.iter()) {
+                prop_assert!(length(a).abs_diff_eq(&length(b), 1e-3));
+            }
+        }
+
+        #[test]
+        fn octonions_are_alternative(x in oct64(), y in oct64()) {
+            let zero = Octonion::new([0.0; 8]);
+            prop_assert!(Octonion::associator(x, x, y).abs_diff_eq(&zero, 1e-8));
+            prop_assert!(Octonion::associator(x, y, y).abs_diff_eq(&zero, 1e-8));
+            prop_assert!(Octonion::associator(x, y, x).abs_diff_eq(&zero, 1e-8));
+        }
+
+        #[test]
+        fn octonion_norm_is_multiplicative(x in oct64(), y in oct64()) {
+            let n = x.norm() * y.norm();
+            prop_assert!((x * y).norm().relative_eq(&n, 1e-9, 1e-12));
+        }
+
+        #[test]
+        fn octonion_conj_reverses_products(x in oct64(), y in oct64()) {
+            let lhs = (x * y).conj();
+            prop_assert!(lhs.abs_diff_eq(&(y.conj() * x.conj()), 1e-8));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serialization_tests {
+    use crate::{
+        DualQuaternion,
+        Entity,
+        Mat4,
+        Mesh,
+        Octonion,
+        Quaternion,
+        Sedenion,
+        Vec3,
+    };
+
+    #[test]
+    fn json_is_compact() {
+        let q = Quaternion::new([1.0f32, 2.0, 3.0, 4.0]);
+        assert_eq!(serde_json::to_string(&q).unwrap(), "[1.0,2.0,3.0,4.0]");
+        let o = Octonion::new([1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        let json = serde_json::to_string(&o).unwrap();
+        assert_eq!(json, "[1.0,2.0,3.0,4.0,5.0,6.0,7.0,8.0]");
+        assert_eq!(serde_json::from_str::<Octonion<f64>>(&json).unwrap(), o);
+        assert_eq!(
+            serde_json::to_string(&Vec3::new([0.5f32, 0.0, -1.0])).unwrap(),
+            "[0.5,0.0,-1.0]"
+        );
+        assert!(serde_json::from_str::<Sedenion<f64>>("[1.0,2.0]").is_err());
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut mesh = Mesh::<f64>::new();
+        mesh.add_points(vec![[1.0, 2.0, 3.0], [-0.5, 0.25, 8.0]]);
+        mesh.normals = vec![[0.0, 0.0, 1.0], [1.0, 0.0, 0.0]];
+        mesh.indices = vec![0, 1, 0];
+        let mut e = Entity::new();
+        e.add_model(mesh);
+        e.orient = Quaternion::from_axis_angle([0.0, 1.0, 0.0], 0.3);
+        let dq =
+            DualQuaternion::from_rotation_translation(e.orient, [1.0, 2.0, 3.0]);
+        let m = Mat4::look_at(
+            Vec3::new([1.0f32, 2.0, 3.0]),
+            Vec3::new([0.0; 3]),
+            Vec3::new([0.0, 1.0, 0.0]),
+        );
+        let s = Sedenion::<f32>::from_slice(&[0.5; 16]);
+
+        let json = serde_json::to_string(&e).unwrap();
+        assert_eq!(serde_json::from_str::<Entity<f64>>(&json).unwrap(), e);
+        let bin = bincode::serialize(&e).unwrap();
+        assert_eq!(bincode::deserialize::<Entity<f64>>(&bin).unwrap(), e);
+
+        let json = serde_json::to_string(&dq).unwrap();
+        assert_eq!(
+            serde_json::from_str::<DualQuaternion<f64>>(&json).unwrap(),
+            dq
+        );
+        let bin = bincode::serialize(&dq).unwrap();
+        assert_eq!(bin.len(), 8 * 8);
+        assert_eq!(
+            bincode::deserialize::<DualQuaternion<f64>>(&bin).unwrap(),
+            dq
+        );
+
+        let bin = bincode::serialize(&m).unwrap();
+        assert_eq!(bin.len(), 16 * 4);
+        assert_eq!(bincode::deserialize::<Mat4<f32>>(&bin).unwrap(), m);
+        let json = serde_json::to_string(&s).unwrap();
+        assert_eq!(serde_json::from_str::<Sedenion<f32>>(&json).unwrap(), s);
+    }
 }
@@ -1,4 +1,11 @@
-use crate::{managers::AssetManager, Entity, Mesh, Octonion, Quaternion};
+use crate::{
+    managers::AssetManager,
+    Entity,
+    Mesh,
+    MeshReport,
+    Octonion,
+    Quaternion,
+};
 use num_traits::{Float, Zero};
 use std::{fs, io, path::PathBuf};
 use tobj::{
@@ -30,21 +37,33 @@ impl AssetManager {
         }
     }
 
+    /// Loads model `n`, repaired as needed. Use
+    /// [`load_checked`](Self::load_checked) to see what was wrong with it.
     pub fn load<T: Float + From<f32>>(
         &self,
         n: &str,
     ) -> Entity<T> {
-        let m = Mesh::<T>::from_tobj_to_mesh(
+        self.load_checked(n).0
+    }
+
+    /// Loads model `n` along with what [`Mesh::repair`] found in its data.
+    pub fn load_checked<T: Float + From<f32>>(
+        &self,
+        n: &str,
+    ) -> (Entity<T>, MeshReport) {
+        let (m, report) = Mesh::<T>::from_tobj_checked(
             &self.objects[self.objects.iter().position(|r| r.name == n).unwrap()]
                 .mesh,
             [T::zero(); 3],
             0.5,
         );
-        Entity {
-            pos:    Octonion::zero(),
+        let e = Entity {
+            pos: Octonion::zero(),
             orient: Quaternion::zero(),
-            len:    m.positions.len(),
-            model:  m,
-        }
+            len: m.positions.len(),
+            model: m,
+            ..Entity::new()
+        };
+        (e, report)
     }
 }
@@ -0,0 +1,343 @@
+use crate::{Mat3, Mat4, Quaternion, Vec3, Vec4};
+use num_traits::{Float, Zero};
+use std::ops::Mul;
+
+impl<T: Float + From<f32>> Mat3<T> {
+    pub fn identity() -> Self {
+        let mut val = [[T::zero(); 3]; 3];
+        for (i, col) in val.iter_mut().enumerate() {
+            col[i] = T::one();
+        }
+        Self { val }
+    }
+
+    pub fn from_cols(
+        x: Vec3<T>,
+        y: Vec3<T>,
+        z: Vec3<T>,
+    ) -> Self {
+        Self {
+            val: [x.val, y.val, z.val],
+        }
+    }
+
+    pub fn col(
+        &self,
+        i: usize,
+    ) -> Vec3<T> {
+        Vec3::new(self.val[i])
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut val = [[T::zero(); 3]; 3];
+        for (c, col) in val.iter_mut().enumerate() {
+            for (r, v) in col.iter_mut().enumerate() {
+                *v = self.val[r][c];
+            }
+        }
+        Self { val }
+    }
+
+    pub fn determinant(&self) -> T {
+        self.col(0).dot(self.col(1).cross(self.col(2)))
+    }
+
+    /// `None` when the matrix is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == T::zero() || !det.is_finite() {
+            return None;
+        }
+        let (a, b, c) = (self.col(0), self.col(1), self.col(2));
+        // rows of the inverse are the cross products of the columns
+        Some(
+            Mat3::from_cols(b.cross(c) / det, c.cross(a) / det, a.cross(b) / det)
+                .transpose(),
+        )
+    }
+
+    pub fn to_quaternion(&self) -> Quaternion<T> {
+        Quaternion::from_mat3(self.val)
+    }
+}
+
+impl<T: Float + From<f32>> Mat4<T> {
+    pub fn identity() -> Self {
+        let mut val = [[T::zero(); 4]; 4];
+        for (i, col) in val.iter_mut().enumerate() {
+            col[i] = T::one();
+        }
+        Self { val }
+    }
+
+    pub fn from_cols(
+        x: Vec4<T>,
+        y: Vec4<T>,
+        z: Vec4<T>,
+        w: Vec4<T>,
+    ) -> Self {
+        Self {
+            val: [x.val, y.val, z.val, w.val],
+        }
+    }
+
+    pub fn col(
+        &self,
+        i: usize,
+    ) -> Vec4<T> {
+        Vec4::new(self.val[i])
+    }
+
+    pub fn from_translation(t: Vec3<T>) -> Self {
+        let mut m = Self::identity();
+        m.val[3][0..3].copy_from_slice(&t.val);
+        m
+    }
+
+    pub fn from_scale(s: Vec3<T>) -> Self {
+        let mut m = Self::identity();
+        for i in 0..3 {
+            m.val[i][i] = s.val[i];
+        }
+        m
+    }
+
+    pub fn from_rotation(q: Quaternion<T>) -> Self { Self { val: q.to_mat4() } }
+
+    /// Upper-left 3x3 block.
+    pub fn truncate(&self) -> Mat3<T> {
+        let mut val = [[T::zero(); 3]; 3];
+        for (c, col) in val.iter_mut().enumerate() {
+            col.copy_from_slice(&self.val[c][0..3]);
+        }
+        Mat3 { val }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut val = [[T::zero(); 4]; 4];
+        for (c, col) in val.iter_mut().enumerate() {
+            for (r, v) in col.iter_mut().enumerate() {
+                *v = self.val[r][c];
+            }
+        }
+        Self { val }
+    }
+
+    /// General inverse by cofactor expansion; `None` when singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let m = |i: usize| self.val[i / 4][i % 4];
+        let mut inv = [T::zero(); 16];
+        inv[0] =
+            m(5) * m(10) * m(15) - m(5) * m(11) * m(14) - m(9) * m(6) * m(15) +
+                m(9) * m(7) * m(14) +
+                m(13) * m(6) * m(11) -
+                m(13) * m(7) * m(10);
+        inv[4] =
+            -m(4) * m(10) * m(15) + m(4) * m(11) * m(14) + m(8) * m(6) * m(15) -
+                m(8) * m(7) * m(14) -
+                m(12) * m(6) * m(11) +
+                m(12) * m(7) * m(10);
+        inv[8] = m(4) * m(9) * m(15) - m(4) * m(11) * m(13) - m(8) * m(5) * m(15) +
+            m(8) * m(7) * m(13) +
+            m(12) * m(5) * m(11) -
+            m(12) * m(7) * m(9);
+        inv[12] =
+            -m(4) * m(9) * m(14) + m(4) * m(10) * m(13) + m(8) * m(5) * m(14) -
+                m(8) * m(6) * m(13) -
+                m(12) * m(5) * m(10) +
+                m(12) * m(6) * m(9);
+        inv[1] =
+            -m(1) * m(10) * m(15) + m(1) * m(11) * m(14) + m(9) * m(2) * m(15) -
+                m(9) * m(3) * m(14) -
+                m(13) * m(2) * m(11) +
+                m(13) * m(3) * m(10);
+        inv[5] =
+            m(0) * m(10) * m(15) - m(0) * m(11) * m(14) - m(8) * m(2) * m(15) +
+                m(8) * m(3) * m(14) +
+                m(12) * m(2) * m(11) -
+                m(12) * m(3) * m(10);
+        inv[9] =
+            -m(0) * m(9) * m(15) + m(0) * m(11) * m(13) + m(8) * m(1) * m(15) -
+                m(8) * m(3) * m(13) -
+                m(12) * m(1) * m(11) +
+                m(12) * m(3) * m(9);
+        inv[13] =
+            m(0) * m(9) * m(14) - m(0) * m(10) * m(13) - m(8) * m(1) * m(14) +
+                m(8) * m(2) * m(13) +
+                m(12) * m(1) * m(10) -
+                m(12) * m(2) * m(9);
+        inv[2] = m(1) * m(6) * m(15) - m(1) * m(7) * m(14) - m(5) * m(2) * m(15) +
+            m(5) * m(3) * m(14) +
+            m(13) * m(2) * m(7) -
+            m(13) * m(3) * m(6);
+        inv[6] = -m(0) * m(6) * m(15) + m(0) * m(7) * m(14) + m(4) * m(2) * m(15) -
+            m(4) * m(3) * m(14) -
+            m(12) * m(2) * m(7) +
+            m(12) * m(3) * m(6);
+        inv[10] = m(0) * m(5) * m(15) - m(0) * m(7) * m(13) - m(4) * m(1) * m(15) +
+            m(4) * m(3) * m(13) +
+            m(12) * m(1) * m(7) -
+            m(12) * m(3) * m(5);
+        inv[14] =
+            -m(0) * m(5) * m(14) + m(0) * m(6) * m(13) + m(4) * m(1) * m(14) -
+                m(4) * m(2) * m(13) -
+                m(12) * m(1) * m(6) +
+                m(12) * m(2) * m(5);
+        inv[3] = -m(1) * m(6) * m(11) + m(1) * m(7) * m(10) + m(5) * m(2) * m(11) -
+            m(5) * m(3) * m(10) -
+            m(9) * m(2) * m(7) +
+            m(9) * m(3) * m(6);
+        inv[7] = m(0) * m(6) * m(11) - m(0) * m(7) * m(10) - m(4) * m(2) * m(11) +
+            m(4) * m(3) * m(10) +
+            m(8) * m(2) * m(7) -
+            m(8) * m(3) * m(6);
+        inv[11] = -m(0) * m(5) * m(11) + m(0) * m(7) * m(9) + m(4) * m(1) * m(11) -
+            m(4) * m(3) * m(9) -
+            m(8) * m(1) * m(7) +
+            m(8) * m(3) * m(5);
+        inv[15] = m(0) * m(5) * m(10) - m(0) * m(6) * m(9) - m(4) * m(1) * m(10) +
+            m(4) * m(2) * m(9) +
+            m(8) * m(1) * m(6) -
+            m(8) * m(2) * m(5);
+
+        let det = m(0) * inv[0] + m(1) * inv[4] + m(2) * inv[8] + m(3) * inv[12];
+        if det == T::zero() || !det.is_finite() {
+            return None;
+        }
+        let mut val = [[T::zero(); 4]; 4];
+        for (i, v) in inv.iter().enumerate() {
+            val[i / 4][i % 4] = *v / det;
+        }
+        Some(Self { val })
+    }
+
+    /// Right-handed perspective projection to OpenGL clip space (`z` in
+    /// `[-1, 1]`); `vert.glsl` flips `y` and remaps `z` for Vulkan.
+    pub fn perspective(
+        fovy: T,
+        aspect: T,
+        near: T,
+        far: T,
+    ) -> Self {
+        let two: T = 2.0.into();
+        let f = T::one() / (fovy / two).tan();
+        let mut val = [[T::zero(); 4]; 4];
+        val[0][0] = f / aspect;
+        val[1][1] = f;
+        val[2][2] = (far + near) / (near - far);
+        val[2][3] = -T::one();
+        val[3][2] = two * far * near / (near - far);
+        Self { val }
+    }
+
+    /// Right-handed orthographic projection to OpenGL clip space.
+    pub fn orthographic(
+        left: T,
+        right: T,
+        bottom: T,
+        top: T,
+        near: T,
+        far: T,
+    ) -> Self {
+        let two: T = 2.0.into();
+        let mut m = Self::identity();
+        m.val[0][0] = two / (right - left);
+        m.val[1][1] = two / (top - bottom);
+        m.val[2][2] = -two / (far - near);
+        m.val[3][0] = -(right + left) / (right - left);
+        m.val[3][1] = -(top + bottom) / (top - bottom);
+        m.val[3][2] = -(far + near) / (far - near);
+        m
+    }
+
+    /// Right-handed view matrix looking from `eye` towards `target`, so the
+    /// camera looks down its local `-z`.
+    pub fn look_at(
+        eye: Vec3<T>,
+        target: Vec3<T>,
+        up: Vec3<T>,
+    ) -> Self {
+        let f = (target - eye).normalize();
+        let s = f.cross(up).normalize();
+        let u = s.cross(f);
+        let zero = T::zero();
+        Self {
+            val: [
+                [s[0], u[0], -f[0], zero],
+                [s[1], u[1], -f[1], zero],
+                [s[2], u[2], -f[2], zero],
+                [-s.dot(eye), -u.dot(eye), f.dot(eye), T::one()],
+            ],
+        }
+    }
+
+    /// Transforms a point, dividing by the resulting `w`.
+    pub fn transform_point(
+        &self,
+        p: Vec3<T>,
+    ) -> Vec3<T> {
+        let v = *self * p.extend(T::one());
+        v.truncate() / v[3]
+    }
+
+    /// Transforms a direction, ignoring translation.
+    pub fn transform_vector(
+        &self,
+        v: Vec3<T>,
+    ) -> Vec3<T> {
+        (*self * v.extend(T::zero())).truncate()
+    }
+}
+
+impl<T: Float + From<f32>> From<Quaternion<T>> for Mat3<T> {
+    fn from(q: Quaternion<T>) -> Self { Self { val: q.to_mat3() } }
+}
+impl<T: Float + From<f32>> From<Quaternion<T>> for Mat4<T> {
+    fn from(q: Quaternion<T>) -> Self { Self::from_rotation(q) }
+}
+
+impl<T: Float + From<f32>> Mul<Vec3<T>> for Mat3<T> {
+    type Output = Vec3<T>;
+
+    fn mul(
+        self,
+        rhs: Vec3<T>,
+    ) -> Vec3<T> {
+        self.col(0) * rhs[0] + self.col(1) * rhs[1] + self.col(2) * rhs[2]
+    }
+}
+impl<T: Float + From<f32>> Mul<Mat3<T>> for Mat3<T> {
+    type Output = Mat3<T>;
+
+    fn mul(
+        self,
+        rhs: Mat3<T>,
+    ) -> Mat3<T> {
+        Mat3::from_cols(self * rhs.col(0), self * rhs.col(1), self * rhs.col(2))
+    }
+}
+impl<T: Float + From<f32>> Mul<Vec4<T>> for Mat4<T> {
+    type Output = Vec4<T>;
+
+    fn mul(
+        self,
+        rhs: Vec4<T>,
+    ) -> Vec4<T> {
+        (0..4).fold(Vec4::zero(), |acc, i| acc + self.col(i) * rhs[i])
+    }
+}
+impl<T: Float + From<f32>> Mul<Mat4<T>> for Mat4<T> {
+    type Output = Mat4<T>;
+
+    fn mul(
+        self,
+        rhs: Mat4<T>,
+    ) -> Mat4<T> {
+        Mat4::from_cols(
+            self * rhs.col(0),
+            self * rhs.col(1),
+            self * rhs.col(2),
+            self * rhs.col(3),
+        )
+    }
+}
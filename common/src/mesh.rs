@@ -1,22 +1,54 @@
-use crate::{Mesh, Quaternion};
-use num_traits::Float;
+use crate::{
+    simd,
+    BoundsCache,
+    Mat4,
+    Mesh,
+    MeshReport,
+    NormalMode,
+    Quaternion,
+    Vec3,
+};
+use num_traits::{Float, Zero};
 use tobj;
+
+mod primitives;
+
+/// Crease angle used when a loaded mesh comes without normals.
+pub const DEFAULT_CREASE: f32 = std::f32::consts::FRAC_PI_3;
+
 impl<T: Float + From<f32>> Mesh<T> {
     pub fn new() -> Self {
         Self {
             positions: vec![],
             normals:   vec![],
+            uvs:       vec![],
+            colors:    vec![],
+            tangents:  vec![],
             indices:   vec![],
-            scale:     0.0,
+            scale:     1.0,
             offset:    [T::zero(); 3],
+            lods:      vec![],
+            bounds:    BoundsCache::default(),
         }
     }
 
+    /// [`from_tobj_checked`](Self::from_tobj_checked) without the report.
     pub fn from_tobj_to_mesh(
         mesh: &tobj::Mesh,
         translation: [T; 3],
         scale: f32,
     ) -> Mesh<T> {
+        Self::from_tobj_checked(mesh, translation, scale).0
+    }
+
+    /// Converts tobj data and runs [`repair`](Self::repair) on it before
+    /// deriving missing normals and the tangents. The report describes the
+    /// data as loaded.
+    pub fn from_tobj_checked(
+        mesh: &tobj::Mesh,
+        translation: [T; 3],
+        scale: f32,
+    ) -> (Mesh<T>, MeshReport) {
         let positions: Vec<[T; 3]> = mesh
             .positions
             .chunks(3)
@@ -27,15 +59,31 @@ impl<T: Float + From<f32>> Mesh<T> {
             .chunks(3)
             .map(|i| [i[0].into(), i[1].into(), i[2].into()])
             .collect();
+        let uvs: Vec<[T; 2]> = mesh
+            .texcoords
+            .chunks(2)
+            .map(|i| [i[0].into(), i[1].into()])
+            .collect();
+        // tobj only parses one UV set and no vertex colors.
+        let uvs = if uvs.is_empty() { vec![] } else { vec![uvs] };
         let indices = mesh.indices.to_vec();
 
-        Mesh {
+        let mut m = Mesh {
             positions,
             normals,
+            uvs,
+            colors: vec![],
+            tangents: vec![],
             indices,
             scale,
             offset: translation,
-        }
+            lods: vec![],
+            bounds: BoundsCache::default(),
+        };
+        // Fills in missing normals too.
+        let report = m.repair();
+        m.compute_tangents();
+        (m, report)
     }
 
     pub fn add_points(
@@ -43,18 +91,329 @@ impl<T: Float + From<f32>> Mesh<T> {
         inp: Vec<[T; 3]>,
     ) -> &Self {
         self.positions.extend(&inp);
+        self.invalidate_bounds();
         self
     }
 
+    /// Rotates positions, normals and tangents by the unit quaternion
+    /// `rotator`. The rotor is converted to a matrix once. Large data that
+    /// is rotated repeatedly is better kept as [`Soa3`](crate::Soa3), which
+    /// has a batch kernel.
     pub fn rotate(
         &mut self,
         rotator: Quaternion<T>,
     ) {
-        for i in 0..self.positions.len() {
-            self.positions[i] = (rotator *
-                Quaternion::fom_imag(self.positions[i]) *
-                rotator.conj())
-            .imag();
+        let m = rotator.to_mat3();
+        simd::transform_scalar(&m, &mut self.positions);
+        self.invalidate_bounds();
+        simd::transform_scalar(&m, &mut self.normals);
+        for t in &mut self.tangents {
+            let mut v = [[t[0], t[1], t[2]]];
+            simd::transform_scalar(&m, &mut v);
+            t[..3].copy_from_slice(&v[0]);
+        }
+    }
+
+    /// Replaces `normals` with ones derived from the triangles in `indices`.
+    /// `Flat` and `Crease` may split vertices, so `positions` and `indices`
+    /// can change too; `Flat` also drops `lods`. Vertices used by no
+    /// triangle get a zero normal. Triangles with an index past the last
+    /// vertex are ignored, and `Flat` drops them. `Flat` and `Crease` also
+    /// drop colours, tangents and UV sets whose length differs from
+    /// `positions`, as [`Mesh::repair`] does.
+    pub fn compute_normals(
+        &mut self,
+        mode: NormalMode,
+    ) -> &mut Self {
+        let n = self.positions.len();
+        // `at` is where each triangle starts in `indices`.
+        let (at, tris): (Vec<usize>, Vec<[usize; 3]>) = self
+            .indices
+            .chunks_exact(3)
+            .enumerate()
+            .map(|(f, t)| (3 * f, [t[0] as usize, t[1] as usize, t[2] as usize]))
+            .filter(|(_, t)| t.iter().all(|&v| v < n))
+            .unzip();
+        // Cross products are twice the triangle area, which is the weight.
+        let weighted: Vec<Vec3<T>> = tris
+            .iter()
+            .map(|t| {
+                let p = |i: usize| Vec3::new(self.positions[t[i]]);
+                (p(1) - p(0)).cross(p(2) - p(0))
+            })
+            .collect();
+        match mode {
+            NormalMode::Flat => {
+                let corners: Vec<u32> =
+                    tris.iter().flatten().map(|&v| v as u32).collect();
+                self.remap(&corners);
+                self.lods.clear();
+                self.normals = weighted
+                    .iter()
+                    .flat_map(|n| {
+                        let n = n.normalize().val;
+                        [n, n, n]
+                    })
+                    .collect();
+                self.indices = (0..corners.len() as u32).collect();
+            }
+            NormalMode::Smooth => {
+                let mut acc = vec![Vec3::zero(); self.positions.len()];
+                for (t, n) in tris.iter().zip(&weighted) {
+                    for &v in t {
+                        acc[v] = acc[v] + *n;
+                    }
+                }
+                self.normals = acc.iter().map(|n| n.normalize().val).collect();
+            }
+            NormalMode::Crease(angle) => {
+                let cos: T = angle.cos().into();
+                let unit: Vec<Vec3<T>> =
+                    weighted.iter().map(|n| n.normalize()).collect();
+                let mut faces = vec![vec![]; self.positions.len()];
+                for (f, t) in tris.iter().enumerate() {
+                    for &v in t {
+                        faces[v].push(f);
+                    }
+                }
+                // Corners whose smoothing groups sum to the same normal share
+                // a vertex. The sum runs over `faces[v]` in a fixed order, so
+                // equal groups give bit-identical normals. The first group
+                // keeps the original index and further ones are appended.
+                let mut splits: Vec<Vec<(Vec3<T>, u32)>> =
+                    vec![vec![]; self.positions.len()];
+                let mut source: Vec<u32> = (0..splits.len() as u32).collect();
+                let mut normals = vec![[T::zero(); 3]; splits.len()];
+                for (f, t) in tris.iter().enumerate() {
+                    for (k, &v) in t.iter().enumerate() {
+                        let n = faces[v]
+                            .iter()
+                            .filter(|&&g| g == f || unit[f].dot(unit[g]) >= cos)
+                            .fold(Vec3::zero(), |acc, &g| acc + weighted[g])
+                            .normalize();
+                        let idx = match splits[v].iter().find(|s| s.0 == n) {
+                            Some(s) => s.1,
+                            None => {
+                                let idx = if splits[v].is_empty() {
+                                    v as u32
+                                } else {
+                                    source.push(v as u32);
+                                    normals.push([T::zero(); 3]);
+                                    source.len() as u32 - 1
+                                };
+                                splits[v].push((n, idx));
+                                normals[idx as usize] = n.val;
+                                idx
+                            }
+                        };
+                        self.indices[at[f] + k] = idx;
+                    }
+                }
+                self.remap(&source);
+                self.normals = normals;
+            }
+        }
+        self
+    }
+
+    /// The live transform, `offset * scale`.
+    pub fn model_matrix(&self) -> Mat4<T> {
+        Mat4::from_translation(Vec3::new(self.offset)) *
+            Mat4::from_scale(Vec3::new([self.scale.into(); 3]))
+    }
+
+    /// Maps positions through the affine `m`, normals through the inverse
+    /// transpose of its linear part and tangents through the linear part
+    /// itself. A mirroring `m` also flips the winding of `indices`, `lods` and
+    /// the tangent signs so faces keep pointing the same way relative to their
+    /// normals. A singular `m` zeroes the normals.
+    pub fn transform(
+        &mut self,
+        m: Mat4<T>,
+    ) -> &mut Self {
+        let linear = m.truncate();
+        let normal = linear.inverse().map(|i| i.transpose());
+        for p in &mut self.positions {
+            *p = m.transform_point(Vec3::new(*p)).val;
+        }
+        self.invalidate_bounds();
+        for n in &mut self.normals {
+            *n = normal.map_or([T::zero(); 3], |nm| {
+                (nm * Vec3::new(*n)).normalize().val
+            });
+        }
+        let mirror = linear.determinant() < T::zero();
+        for t in &mut self.tangents {
+            let v = (linear * Vec3::new([t[0], t[1], t[2]])).normalize();
+            let w = if mirror { -t[3] } else { t[3] };
+            *t = [v[0], v[1], v[2], w];
+        }
+        if mirror {
+            let lods = self.lods.iter_mut().map(|l| &mut l.indices);
+            for indices in std::iter::once(&mut self.indices).chain(lods) {
+                for t in indices.chunks_exact_mut(3) {
+                    t.swap(1, 2);
+                }
+            }
+        }
+        self
+    }
+
+    /// Bakes the live `scale` and `offset` into the vertex data, with
+    /// `rotation` applied in between: `p -> rotation * (scale * p) +
+    /// offset`. Afterwards `scale` is 1 and `offset` is zero, so the mesh
+    /// looks the same to any consumer that honours the live fields.
+    pub fn apply_transform(
+        &mut self,
+        rotation: Option<Quaternion<T>>,
+    ) -> &mut Self {
+        let r = rotation.map_or(Mat4::identity(), Mat4::from_rotation);
+        let m = Mat4::from_translation(Vec3::new(self.offset)) *
+            r *
+            Mat4::from_scale(Vec3::new([self.scale.into(); 3]));
+        self.scale = 1.0;
+        self.offset = [T::zero(); 3];
+        self.transform(m)
+    }
+
+    /// Copy with `scale` and `offset` baked in.
+    pub fn baked(&self) -> Self {
+        let mut m = self.clone();
+        m.apply_transform(None);
+        m
+    }
+
+    /// Names the first per-vertex attribute whose length is neither zero nor
+    /// that of `positions`.
+    pub fn mismatched_attribute(&self) -> Option<&'static str> {
+        self.mismatched_attributes().first().copied()
+    }
+
+    /// Every per-vertex attribute whose length is neither zero nor that of
+    /// `positions`.
+    pub fn mismatched_attributes(&self) -> Vec<&'static str> {
+        let n = self.positions.len();
+        let bad = |len: usize| len != 0 && len != n;
+        let mut out = vec![];
+        if bad(self.normals.len()) {
+            out.push("normals");
+        }
+        if self.uvs.iter().any(|set| bad(set.len())) {
+            out.push("uvs");
+        }
+        if bad(self.colors.len()) {
+            out.push("colors");
+        }
+        if bad(self.tangents.len()) {
+            out.push("tangents");
+        }
+        out
+    }
+
+    /// Fills `tangents` the way MikkTSpace does: each face's `dP/du` is
+    /// projected into the tangent plane of every corner's normal and
+    /// weighted by the corner angle. Needs a normal and a set 0 UV per vertex,
+    /// otherwise `tangents` is left empty. Triangles with an index past the
+    /// last vertex are ignored.
+    ///
+    /// Agrees with the reference implementation wherever it gives all
+    /// corners of a vertex the same tangent. It doesn't where it would split
+    /// the vertex: a vertex shared by faces with opposite UV winding takes
+    /// the winding with more total angle, so mirrored seams should already
+    /// be split.
+    pub fn compute_tangents(&mut self) -> &mut Self {
+        let n = self.positions.len();
+        self.tangents.clear();
+        let uvs = match self.uvs.first() {
+            Some(uvs) if uvs.len() == n && self.normals.len() == n => uvs,
+            _ => return self,
+        };
+        // Index 0 collects faces that keep the UV winding, 1 mirrored ones.
+        let mut acc = vec![[Vec3::zero(); 2]; n];
+        let mut weight = vec![[T::zero(); 2]; n];
+        for t in self.indices.chunks_exact(3) {
+            let t = [t[0] as usize, t[1] as usize, t[2] as usize];
+            if t.iter().any(|&v| v >= n) {
+                continue;
+            }
+            let p = |k: usize| Vec3::new(self.positions[t[k % 3]]);
+            let (uv0, uv1, uv2) = (uvs[t[0]], uvs[t[1]], uvs[t[2]]);
+            let t21 = [uv1[0] - uv0[0], uv1[1] - uv0[1]];
+            let t31 = [uv2[0] - uv0[0], uv2[1] - uv0[1]];
+            let area = t21[0] * t31[1] - t21[1] * t31[0];
+            if area == T::zero() {
+                continue;
+            }
+            let side = if area > T::zero() { 0 } else { 1 };
+            let os =
+                ((p(1) - p(0)) * t31[1] - (p(2) - p(0)) * t21[1]) * area.signum();
+            for (k, &v) in t.iter().enumerate() {
+                let nrm = Vec3::new(self.normals[v]);
+                let proj = |x: Vec3<T>| (x - nrm * nrm.dot(x)).normalize();
+                let e1 = proj(p(k + 1) - p(k));
+                let e2 = proj(p(k + 2) - p(k));
+                let angle = e1.dot(e2).max(-T::one()).min(T::one()).acos();
+                acc[v][side] = acc[v][side] + proj(os) * angle;
+                weight[v][side] = weight[v][side] + angle;
+            }
+        }
+        self.tangents = acc
+            .iter()
+            .zip(&weight)
+            .zip(&self.normals)
+            .map(|((a, w), &nrm)| {
+                let (side, sign) = if w[1] > w[0] {
+                    (1, -T::one())
+                } else {
+                    (0, T::one())
+                };
+                let t = a[side].normalize();
+                let t = if t.is_zero() { perpendicular(nrm) } else { t };
+                [t[0], t[1], t[2], sign]
+            })
+            .collect();
+        self
+    }
+
+    /// Rebuilds every per-vertex attribute so that vertex `i` becomes a copy
+    /// of old vertex `source[i]`. Does not touch `indices` or `lods`.
+    /// Attributes with a different length than `positions` are dropped.
+    pub(crate) fn remap(
+        &mut self,
+        source: &[u32],
+    ) {
+        fn pick<A: Copy>(
+            v: &mut Vec<A>,
+            source: &[u32],
+            n: usize,
+        ) {
+            if v.len() == n {
+                *v = source.iter().map(|&i| v[i as usize]).collect();
+            } else {
+                v.clear();
+            }
         }
+        let n = self.positions.len();
+        self.uvs.retain(|set| set.len() == n);
+        pick(&mut self.normals, source, n);
+        for set in &mut self.uvs {
+            pick(set, source, n);
+        }
+        pick(&mut self.colors, source, n);
+        pick(&mut self.tangents, source, n);
+        pick(&mut self.positions, source, n);
+        self.invalidate_bounds();
     }
 }
+
+/// Some unit vector perpendicular to `n`, for vertices whose UVs give no
+/// tangent direction.
+fn perpendicular<T: Float + From<f32>>(n: [T; 3]) -> Vec3<T> {
+    let n = Vec3::new(n);
+    let a = if n[0].abs() < 0.9.into() {
+        Vec3::new([T::one(), T::zero(), T::zero()])
+    } else {
+        Vec3::new([T::zero(), T::one(), T::zero()])
+    };
+    (a - n * n.dot(a)).normalize()
+}
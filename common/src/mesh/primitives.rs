@@ -0,0 +1,429 @@
+//! Procedural meshes. All are centred on the origin with `+y` up, wound
+//! counter-clockwise seen from outside, and come with normals, one UV set
+//! and tangents. Surfaces of revolution duplicate the seam column so `u`
+//! runs from 0 to 1; the copies have bit-identical positions.
+use crate::{Mesh, Vec3};
+use num_traits::Float;
+use std::collections::HashMap;
+
+impl<T: Float + From<f32>> Mesh<T> {
+    /// `width` along `x` by `depth` along `z`, facing `+y`. `v` grows
+    /// towards `-z`.
+    pub fn plane(
+        width: T,
+        depth: T,
+        segments: [usize; 2],
+    ) -> Self {
+        let half: T = 0.5.into();
+        let mut m = Self::primitive();
+        m.face(
+            [-width * half, T::zero(), depth * half],
+            [width, T::zero(), T::zero()],
+            [T::zero(), T::zero(), -depth],
+            segments,
+        );
+        m.finish()
+    }
+
+    /// Box with edges `size`, each face split into `segments` by `segments`
+    /// quads with its own vertices.
+    pub fn cuboid(
+        size: [T; 3],
+        segments: usize,
+    ) -> Self {
+        let half: T = 0.5.into();
+        let (a, b, c) = (size[0] * half, size[1] * half, size[2] * half);
+        let (z, s) = (T::zero(), [segments; 2]);
+        let two: T = 2.0.into();
+        let (a2, b2, c2) = (a * two, b * two, c * two);
+        let mut m = Self::primitive();
+        m.face([a, -b, c], [z, z, -c2], [z, b2, z], s);
+        m.face([-a, -b, -c], [z, z, c2], [z, b2, z], s);
+        m.face([-a, b, c], [a2, z, z], [z, z, -c2], s);
+        m.face([-a, -b, -c], [a2, z, z], [z, z, c2], s);
+        m.face([-a, -b, c], [a2, z, z], [z, b2, z], s);
+        m.face([a, -b, -c], [-a2, z, z], [z, b2, z], s);
+        m.finish()
+    }
+
+    /// Latitude-longitude sphere with `rings` bands from pole to pole.
+    pub fn uv_sphere(
+        radius: T,
+        segments: usize,
+        rings: usize,
+    ) -> Self {
+        let rings = rings.max(2);
+        let pi = (-T::one()).acos();
+        let profile: Vec<_> = (0..=rings)
+            .map(|r| {
+                let v = fraction::<T>(r, rings);
+                let (s, c) = pole_exact(v * pi, r == 0 || r == rings);
+                ([radius * s, -radius * c], [s, -c], v)
+            })
+            .collect();
+        let mut m = Self::primitive();
+        m.revolve(segments, &profile);
+        m.finish()
+    }
+
+    /// Icosahedron split `subdivisions` times and pushed onto the sphere.
+    /// UVs follow [`uv_sphere`](Self::uv_sphere); triangles across the seam
+    /// get copies of their vertices with `u > 1`.
+    pub fn icosphere(
+        radius: T,
+        subdivisions: usize,
+    ) -> Self {
+        let (o, l) = (T::zero(), T::one());
+        let five: T = 5.0.into();
+        let t = (l + five.sqrt()) / (l + l);
+        let mut dirs: Vec<Vec3<T>> = [
+            [-l, t, o],
+            [l, t, o],
+            [-l, -t, o],
+            [l, -t, o],
+            [o, -l, t],
+            [o, l, t],
+            [o, -l, -t],
+            [o, l, -t],
+            [t, o, -l],
+            [t, o, l],
+            [-t, o, -l],
+            [-t, o, l],
+        ]
+        .iter()
+        .map(|&p| Vec3::new(p).normalize())
+        .collect();
+        let mut faces: Vec<[u32; 3]> = vec![
+            [0, 11, 5],
+            [0, 5, 1],
+            [0, 1, 7],
+            [0, 7, 10],
+            [0, 10, 11],
+            [1, 5, 9],
+            [5, 11, 4],
+            [11, 10, 2],
+            [10, 7, 6],
+            [7, 1, 8],
+            [3, 9, 4],
+            [3, 4, 2],
+            [3, 2, 6],
+            [3, 6, 8],
+            [3, 8, 9],
+            [4, 9, 5],
+            [2, 4, 11],
+            [6, 2, 10],
+            [8, 6, 7],
+            [9, 8, 1],
+        ];
+        for _ in 0..subdivisions {
+            let mut mid = HashMap::new();
+            let mut split = |a: u32, b: u32, dirs: &mut Vec<Vec3<T>>| {
+                *mid.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    let d = dirs[a as usize] + dirs[b as usize];
+                    dirs.push(d.normalize());
+                    dirs.len() as u32 - 1
+                })
+            };
+            faces = faces
+                .iter()
+                .flat_map(|&[a, b, c]| {
+                    let ab = split(a, b, &mut dirs);
+                    let bc = split(b, c, &mut dirs);
+                    let ca = split(c, a, &mut dirs);
+                    vec![[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+                })
+                .collect();
+        }
+
+        let two_pi = (-T::one()).acos() * 2.0.into();
+        let (two, tiny): (T, T) = (2.0.into(), 1e-6.into());
+        let uv = |d: Vec3<T>| {
+            let u = (-d[2]).atan2(d[0]) / two_pi;
+            let u = if u < T::zero() { u + T::one() } else { u };
+            [
+                u,
+                (-d[1]).max(-T::one()).min(T::one()).acos() * two / two_pi,
+            ]
+        };
+        let pole = |d: Vec3<T>| d[0].abs() < tiny && d[2].abs() < tiny;
+        let mut m = Self::primitive();
+        let mut plain = HashMap::new();
+        let mut wrapped = HashMap::new();
+        for f in &faces {
+            let d = [0, 1, 2].map(|k| dirs[f[k] as usize]);
+            let mut uvs = d.map(uv);
+            let us: Vec<T> =
+                (0..3).filter(|&k| !pole(d[k])).map(|k| uvs[k][0]).collect();
+            let lo = us.iter().fold(T::infinity(), |a, &b| a.min(b));
+            let hi = us.iter().fold(T::neg_infinity(), |a, &b| a.max(b));
+            let seam = hi - lo > 0.5.into();
+            for uv in uvs.iter_mut() {
+                if seam && uv[0] < 0.5.into() {
+                    uv[0] = uv[0] + T::one();
+                }
+            }
+            let others: Vec<T> =
+                (0..3).filter(|&k| !pole(d[k])).map(|k| uvs[k][0]).collect();
+            let mut tri = [0; 3];
+            for k in 0..3 {
+                let p = (d[k] * radius).val;
+                tri[k] = if pole(d[k]) {
+                    let n: T = (others.len() as f32).into();
+                    let u = others.iter().fold(T::zero(), |a, &b| a + b) / n;
+                    m.vertex(p, d[k].val, [u, uvs[k][1]])
+                } else {
+                    let cache = if seam && uvs[k][0] >= T::one() {
+                        &mut wrapped
+                    } else {
+                        &mut plain
+                    };
+                    *cache
+                        .entry(f[k])
+                        .or_insert_with(|| m.vertex(p, d[k].val, uvs[k]))
+                };
+            }
+            m.indices.extend_from_slice(&tri);
+        }
+        m.finish()
+    }
+
+    /// Open tube of `rings` bands along `y` closed by two flat caps.
+    pub fn cylinder(
+        radius: T,
+        height: T,
+        segments: usize,
+        rings: usize,
+    ) -> Self {
+        let rings = rings.max(1);
+        let half: T = 0.5.into();
+        let profile: Vec<_> = (0..=rings)
+            .map(|r| {
+                let v = fraction::<T>(r, rings);
+                ([radius, height * (v - half)], [T::one(), T::zero()], v)
+            })
+            .collect();
+        let mut m = Self::primitive();
+        m.revolve(segments, &profile);
+        m.disc(radius, -height * half, false, segments);
+        m.disc(radius, height * half, true, segments);
+        m.finish()
+    }
+
+    /// Apex at `+height / 2`, capped base at `-height / 2`.
+    pub fn cone(
+        radius: T,
+        height: T,
+        segments: usize,
+        rings: usize,
+    ) -> Self {
+        let rings = rings.max(1);
+        let half: T = 0.5.into();
+        let slope = Vec3::new([height, radius, T::zero()]).normalize();
+        let profile: Vec<_> = (0..=rings)
+            .map(|r| {
+                let v = fraction::<T>(r, rings);
+                let s = if r == rings {
+                    T::zero()
+                } else {
+                    radius * (T::one() - v)
+                };
+                ([s, height * (v - half)], [slope[0], slope[1]], v)
+            })
+            .collect();
+        let mut m = Self::primitive();
+        m.revolve(segments, &profile);
+        m.disc(radius, -height * half, false, segments);
+        m.finish()
+    }
+
+    /// Ring of radius `major` around `y` with a tube of radius `minor`,
+    /// `sides` quads around the tube.
+    pub fn torus(
+        major: T,
+        minor: T,
+        segments: usize,
+        sides: usize,
+    ) -> Self {
+        let sides = sides.max(3);
+        let two_pi = (-T::one()).acos() * 2.0.into();
+        let profile: Vec<_> = (0..=sides)
+            .map(|r| {
+                let v = fraction::<T>(r, sides);
+                let (s, c) = (fraction::<T>(r % sides, sides) * two_pi).sin_cos();
+                ([major + minor * c, minor * s], [c, s], v)
+            })
+            .collect();
+        let mut m = Self::primitive();
+        m.revolve(segments, &profile);
+        m.finish()
+    }
+
+    /// Cylinder of `height` between two hemispheres of `radius`, so the
+    /// total height is `height + 2 * radius`. Each hemisphere has `rings`
+    /// bands; `v` is proportional to arc length.
+    pub fn capsule(
+        radius: T,
+        height: T,
+        segments: usize,
+        rings: usize,
+    ) -> Self {
+        let rings = rings.max(1);
+        let half: T = 0.5.into();
+        let half_pi = (-T::one()).acos() * half;
+        let total = half_pi * radius * 2.0.into() + height;
+        let mut profile = vec![];
+        for r in 0..=rings {
+            let a = fraction::<T>(r, rings) * half_pi;
+            let (s, c) = pole_exact(a, r == 0);
+            let y = -height * half - radius * c;
+            profile.push(([radius * s, y], [s, -c], a * radius / total));
+        }
+        for r in 0..=rings {
+            let a = fraction::<T>(r, rings) * half_pi;
+            let (c, s) = pole_exact(half_pi - a, r == rings);
+            let y = height * half + radius * s;
+            let v = (half_pi * radius + height + a * radius) / total;
+            profile.push(([radius * c, y], [c, s], v));
+        }
+        let mut m = Self::primitive();
+        m.revolve(segments, &profile);
+        m.finish()
+    }
+
+    fn primitive() -> Self {
+        let mut m = Self::new();
+        m.uvs = vec![vec![]];
+        m
+    }
+
+    fn finish(mut self) -> Self {
+        self.remove_unused().compute_tangents();
+        self
+    }
+
+    fn vertex(
+        &mut self,
+        p: [T; 3],
+        n: [T; 3],
+        uv: [T; 2],
+    ) -> u32 {
+        self.positions.push(p);
+        self.normals.push(n);
+        self.uvs[0].push(uv);
+        self.positions.len() as u32 - 1
+    }
+
+    /// Grid of `(cols + 1) * (rows + 1)` vertices, row by row, with the
+    /// triangles wound along `du x dv`. Triangles with two identical corners
+    /// are left out, which can leave a vertex of a collapsed row unused.
+    fn grid(
+        &mut self,
+        cols: usize,
+        rows: usize,
+        mut at: impl FnMut(usize, usize) -> ([T; 3], [T; 3], [T; 2]),
+    ) {
+        let base = self.positions.len() as u32;
+        for r in 0..=rows {
+            for c in 0..=cols {
+                let (p, n, uv) = at(c, r);
+                self.vertex(p, n, uv);
+            }
+        }
+        let w = cols as u32 + 1;
+        for r in 0..rows as u32 {
+            for c in 0..cols as u32 {
+                let a = base + r * w + c;
+                for t in [[a, a + 1, a + w + 1], [a, a + w + 1, a + w]].iter() {
+                    let p = |k: usize| self.positions[t[k] as usize];
+                    if p(0) != p(1) && p(1) != p(2) && p(0) != p(2) {
+                        self.indices.extend_from_slice(t);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Flat parallelogram `origin + u * du + v * dv` facing `du x dv`.
+    fn face(
+        &mut self,
+        origin: [T; 3],
+        du: [T; 3],
+        dv: [T; 3],
+        segments: [usize; 2],
+    ) {
+        let [cols, rows] = [segments[0].max(1), segments[1].max(1)];
+        let (o, du, dv) = (Vec3::new(origin), Vec3::new(du), Vec3::new(dv));
+        let n = du.cross(dv).normalize().val;
+        self.grid(cols, rows, |c, r| {
+            let (u, v) = (fraction::<T>(c, cols), fraction::<T>(r, rows));
+            ((o + du * u + dv * v).val, n, [u, v])
+        });
+    }
+
+    /// Turns each `([radius, y], [normal radius, normal y], v)` profile row
+    /// about `y`, bottom row first.
+    fn revolve(
+        &mut self,
+        segments: usize,
+        profile: &[([T; 2], [T; 2], T)],
+    ) {
+        let segments = segments.max(3);
+        let two_pi = (-T::one()).acos() * 2.0.into();
+        self.grid(segments, profile.len() - 1, |c, r| {
+            let u = fraction::<T>(c, segments);
+            let (s, co) =
+                (fraction::<T>(c % segments, segments) * two_pi).sin_cos();
+            let ([rad, y], [nr, ny], v) = profile[r];
+            ([rad * co, y, -rad * s], [nr * co, ny, -nr * s], [u, v])
+        });
+    }
+
+    /// Flat cap at height `y`, facing `+y` when `up`.
+    fn disc(
+        &mut self,
+        radius: T,
+        y: T,
+        up: bool,
+        segments: usize,
+    ) {
+        let segments = segments.max(3);
+        let two_pi = (-T::one()).acos() * 2.0.into();
+        let half: T = 0.5.into();
+        let n = if up { T::one() } else { -T::one() };
+        let nrm = [T::zero(), n, T::zero()];
+        let center = self.vertex([T::zero(), y, T::zero()], nrm, [half, half]);
+        for c in 0..=segments {
+            let (s, co) =
+                (fraction::<T>(c % segments, segments) * two_pi).sin_cos();
+            let uv = [half + half * co, half + half * s * n];
+            self.vertex([radius * co, y, -radius * s], nrm, uv);
+        }
+        for c in 0..segments as u32 {
+            let (a, b) = (center + 1 + c, center + 2 + c);
+            let t = if up { [center, a, b] } else { [center, b, a] };
+            self.indices.extend_from_slice(&t);
+        }
+    }
+}
+
+fn fraction<T: Float>(
+    i: usize,
+    n: usize,
+) -> T {
+    let c = |x: usize| num_traits::cast::<usize, T>(x).unwrap();
+    c(i) / c(n)
+}
+
+/// `(sin, cos)` of `a`, with the sine forced to exactly zero at a pole so
+/// the ring collapses to one point.
+fn pole_exact<T: Float>(
+    a: T,
+    pole: bool,
+) -> (T, T) {
+    let (s, c) = a.sin_cos();
+    if pole {
+        (T::zero(), c.signum())
+    } else {
+        (s, c)
+    }
+}
@@ -1,6 +1,6 @@
-use crate::{Octonion, Quaternion, WhichFloat};
+use crate::{CayleyDickson, Hypercomplex, Octonion, Quaternion, WhichFloat};
 use num_traits::{identities::One, Float, Zero};
-use std::ops::{Add, Mul, Sub};
+use std::ops::{Add, Div, Mul, Neg, Sub};
 use vulkano::pipeline::vertex::{VertexMember, VertexMemberTy};
 
 impl<T: Float + From<f32>> Octonion<T> {
@@ -11,16 +11,10 @@ impl<T: Float + From<f32>> Octonion<T> {
         }
     }
 
-    pub fn conj(&self) -> Self {
-        Self {
-            q1: self.q1.conj(),
-            q2: self.q2.inv(),
-        }
-    }
+    pub fn conj(&self) -> Self { CayleyDickson::from(*self).conj().into() }
 
     pub fn conj_mut(&mut self) -> &Self {
-        self.q1.conj_mut();
-        self.q2.inv_mut();
+        *self = self.conj();
         self
     }
 
@@ -39,6 +33,50 @@ impl<T: Float + From<f32>> Octonion<T> {
         self * (Octonion::one() - t) + other * t
     }
 
+    pub fn identity() -> Self {
+        Self {
+            q1: Quaternion::identity(),
+            q2: Quaternion::zero(),
+        }
+    }
+
+    pub fn norm_sqr(&self) -> T { CayleyDickson::from(*self).norm_sqr() }
+
+    pub fn norm(&self) -> T { self.norm_sqr().sqrt() }
+
+    /// Scales to unit norm. Zero input gives non-finite components.
+    pub fn u(&self) -> Self { *self / self.norm() }
+
+    /// Multiplicative inverse, `conj / |o|^2`.
+    pub fn inverse(&self) -> Self { self.conj() / self.norm_sqr() }
+
+    pub fn checked_inverse(&self) -> Option<Self> {
+        let n2 = self.norm_sqr();
+        if n2 == T::zero() || !n2.is_finite() {
+            None
+        } else {
+            Some(self.conj() / n2)
+        }
+    }
+
+    /// `(ab)c - a(bc)`, which is non-zero for octonions in general but
+    /// vanishes whenever two of the arguments are equal.
+    pub fn associator(
+        a: Octonion<T>,
+        b: Octonion<T>,
+        c: Octonion<T>,
+    ) -> Octonion<T> {
+        (a * b) * c - a * (b * c)
+    }
+
+    /// `ab - ba`.
+    pub fn commutator(
+        a: Octonion<T>,
+        b: Octonion<T>,
+    ) -> Octonion<T> {
+        a * b - b * a
+    }
+
     // pub fn lerp_q(
     //     self,
     //     other: Qaternion<T>,
@@ -105,10 +143,7 @@ impl<T: Float + From<f32>> Mul<Octonion<T>> for Octonion<T> {
         self,
         rhs: Octonion<T>,
     ) -> Octonion<T> {
-        Octonion {
-            q1: self.q1 * rhs.q1 - rhs.q2.conj() * self.q2,
-            q2: rhs.q2 * self.q1 + self.q2 * rhs.q1.conj(),
-        }
+        (CayleyDickson::from(self) * CayleyDickson::from(rhs)).into()
     }
 }
 impl<T: Float + From<f32>> Mul<Quaternion<T>> for Octonion<T> {
@@ -175,3 +210,41 @@ impl<T: Float + From<f32>> Sub<Octonion<T>> for Octonion<T> {
         }
     }
 }
+
+impl<T: Float + From<f32>> Div<T> for Octonion<T> {
+    type Output = Octonion<T>;
+
+    fn div(
+        self,
+        rhs: T,
+    ) -> Octonion<T> {
+        Octonion {
+            q1: self.q1 / rhs,
+            q2: self.q2 / rhs,
+        }
+    }
+}
+impl<T: Float + From<f32>> Div<Octonion<T>> for Octonion<T> {
+    type Output = Octonion<T>;
+
+    /// Right division, `self * rhs^-1`. Octonions are alternative, so
+    /// `(a / b) * b == a` still holds.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(
+        self,
+        rhs: Octonion<T>,
+    ) -> Octonion<T> {
+        self * rhs.inverse()
+    }
+}
+
+impl<T: Float + From<f32>> Neg for Octonion<T> {
+    type Output = Octonion<T>;
+
+    fn neg(self) -> Octonion<T> {
+        Octonion {
+            q1: -self.q1,
+            q2: -self.q2,
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use crate::{Mesh, OptimizeReport};
+use num_traits::Float;
+use std::collections::{HashMap, VecDeque};
+
+/// Post-transform cache size assumed when reordering and reporting.
+pub const CACHE_SIZE: usize = 32;
+
+impl<T: Float + From<f32>> Mesh<T> {
+    /// Welds within `tolerance`, then reorders triangles for the vertex
+    /// cache and vertices for fetch locality.
+    ///
+    /// # Panics
+    ///
+    /// If `indices` refer past the last vertex; see [`Mesh::repair`].
+    pub fn optimize(
+        &mut self,
+        tolerance: T,
+    ) -> OptimizeReport {
+        let vertices_before = self.positions.len();
+        let acmr_before = self.acmr(CACHE_SIZE);
+        self.weld(tolerance)
+            .optimize_vertex_cache()
+            .optimize_vertex_fetch();
+        OptimizeReport {
+            vertices_before,
+            vertices_after: self.positions.len(),
+            acmr_before,
+            acmr_after: self.acmr(CACHE_SIZE),
+        }
+    }
+
+    /// Merges vertices whose positions and other attributes all agree within
+    /// `tolerance`, drops the triangles that collapse and the vertices no
+    /// triangle uses. A zero tolerance removes exact duplicates only.
+    /// Vertices with a non-finite coordinate are never merged. Drops `lods`.
+    ///
+    /// # Panics
+    ///
+    /// If `indices` refer past the last vertex; see [`Mesh::repair`].
+    pub fn weld(
+        &mut self,
+        tolerance: T,
+    ) -> &mut Self {
+        let cell = |x: T| -> i64 {
+            if tolerance > T::zero() {
+                (x / tolerance).floor().to_i64().unwrap_or(i64::MAX)
+            } else {
+                (x + T::zero()).to_f64().map_or(0, |f| f.to_bits() as i64)
+            }
+        };
+        let reach = if tolerance > T::zero() { 1 } else { 0 };
+        let mut grid: HashMap<[i64; 3], Vec<u32>> = HashMap::new();
+        let mut rep: Vec<u32> = Vec::with_capacity(self.positions.len());
+        for v in 0..self.positions.len() {
+            let p = self.positions[v];
+            if !p.iter().all(|x| x.is_finite()) {
+                rep.push(v as u32);
+                continue;
+            }
+            let c = [cell(p[0]), cell(p[1]), cell(p[2])];
+            let mut found = None;
+            'search: for dx in -reach..=reach {
+                for dy in -reach..=reach {
+                    for dz in -reach..=reach {
+                        let key = [
+                            c[0].saturating_add(dx),
+                            c[1].saturating_add(dy),
+                            c[2].saturating_add(dz),
+                        ];
+                        for &r in grid.get(&key).into_iter().flatten() {
+                            if self.same_vertex(v, r as usize, tolerance) {
+                                found = Some(r);
+                                break 'search;
+                            }
+                        }
+                    }
+                }
+            }
+            rep.push(found.unwrap_or_else(|| {
+                grid.entry(c).or_default().push(v as u32);
+                v as u32
+            }));
+        }
+        let mut indices = Vec::with_capacity(self.indices.len());
+        for t in self.indices.chunks_exact(3) {
+            let t = [rep[t[0] as usize], rep[t[1] as usize], rep[t[2] as usize]];
+            if t[0] != t[1] && t[1] != t[2] && t[0] != t[2] {
+                indices.extend_from_slice(&t);
+            }
+        }
+        self.indices = indices;
+        self.lods.clear();
+        self.remove_unused()
+    }
+
+    fn same_vertex(
+        &self,
+        a: usize,
+        b: usize,
+        tolerance: T,
+    ) -> bool {
+        fn near<T: Float>(
+            x: &[T],
+            y: &[T],
+            tolerance: T,
+        ) -> bool {
+            x.iter().zip(y).all(|(x, y)| (*x - *y).abs() <= tolerance)
+        }
+        let attr = |v: &[[T; 3]]| v.is_empty() || near(&v[a], &v[b], tolerance);
+        attr(&self.positions) &&
+            attr(&self.normals) &&
+            self.uvs
+                .iter()
+                .all(|s| s.is_empty() || near(&s[a], &s[b], tolerance)) &&
+            (self.colors.is_empty() ||
+                near(&self.colors[a], &self.colors[b], tolerance)) &&
+            (self.tangents.is_empty() ||
+                near(&self.tangents[a], &self.tangents[b], tolerance))
+    }
+
+    /// Drops vertices no triangle refers to, keeping the order of the rest.
+    ///
+    /// # Panics
+    ///
+    /// If `indices` refer past the last vertex; see [`Mesh::repair`].
+    pub fn remove_unused(&mut self) -> &mut Self {
+        let mut used = vec![false; self.positions.len()];
+        for &i in &self.indices {
+            used[i as usize] = true;
+        }
+        let order: Vec<u32> = (0..used.len() as u32)
+            .filter(|&v| used[v as usize])
+            .collect();
+        self.renumber(&order)
+    }
+
+    /// Renumbers vertices in the order the triangles first use them, so the
+    /// vertex fetch walks memory mostly forwards. Unused vertices are dropped.
+    ///
+    /// # Panics
+    ///
+    /// If `indices` refer past the last vertex; see [`Mesh::repair`].
+    pub fn optimize_vertex_fetch(&mut self) -> &mut Self {
+        let mut seen = vec![false; self.positions.len()];
+        let mut order = vec![];
+        for &i in &self.indices {
+            if !seen[i as usize] {
+                seen[i as usize] = true;
+                order.push(i);
+            }
+        }
+        self.renumber(&order)
+    }
+
+    /// Makes old vertex `order[i]` the new vertex `i` and rewrites `indices`
+    /// and `lods` to match. Every vertex in `indices` has to appear in
+    /// `order`; `lods` are dropped if they use one that does not.
+    pub(crate) fn renumber(
+        &mut self,
+        order: &[u32],
+    ) -> &mut Self {
+        let mut new = vec![u32::MAX; self.positions.len()];
+        for (n, &o) in order.iter().enumerate() {
+            new[o as usize] = n as u32;
+        }
+        for i in &mut self.indices {
+            *i = new[*i as usize];
+        }
+        for i in self.lods.iter_mut().flat_map(|l| &mut l.indices) {
+            *i = new[*i as usize];
+        }
+        if self
+            .lods
+            .iter()
+            .flat_map(|l| &l.indices)
+            .any(|&i| i == u32::MAX)
+        {
+            self.lods.clear();
+        }
+        self.remap(order);
+        self
+    }
+
+    /// Reorders triangles with Forsyth's linear-speed vertex cache
+    /// optimisation, simulating an LRU cache of [`CACHE_SIZE`] entries.
+    /// When no triangle touches the cache, the first one not yet emitted
+    /// starts the next run.
+    ///
+    /// # Panics
+    ///
+    /// If `indices` refer past the last vertex; see [`Mesh::repair`].
+    pub fn optimize_vertex_cache(&mut self) -> &mut Self {
+        let tris: Vec<[usize; 3]> = self
+            .indices
+            .chunks_exact(3)
+            .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+            .collect();
+        let mut adj = vec![vec![]; self.positions.len()];
+        for (f, t) in tris.iter().enumerate() {
+            for &v in t {
+                adj[v].push(f);
+            }
+        }
+        let mut pos: Vec<Option<usize>> = vec![None; adj.len()];
+        let mut score: Vec<f32> =
+            adj.iter().map(|a| forsyth_score(None, a.len())).collect();
+        let tri_score = |t: &[usize; 3], score: &[f32]| {
+            score[t[0]] + score[t[1]] + score[t[2]]
+        };
+        let mut emitted = vec![false; tris.len()];
+        let mut cache: Vec<usize> = vec![];
+        let mut out = Vec::with_capacity(self.indices.len());
+        let mut best = None;
+        let mut cursor = 0;
+        for _ in 0..tris.len() {
+            let f = match best {
+                Some(f) => f,
+                None => {
+                    while emitted[cursor] {
+                        cursor += 1;
+                    }
+                    cursor
+                }
+            };
+            emitted[f] = true;
+            let t = tris[f];
+            out.extend(t.iter().map(|&v| v as u32));
+            for &v in &t {
+                let k = adj[v].iter().position(|&g| g == f).unwrap();
+                adj[v].swap_remove(k);
+            }
+            let old = std::mem::take(&mut cache);
+            cache.extend_from_slice(&t);
+            cache.extend(old.iter().copied().filter(|v| !t.contains(v)));
+            for &v in cache.iter().skip(CACHE_SIZE) {
+                pos[v] = None;
+                score[v] = forsyth_score(None, adj[v].len());
+            }
+            cache.truncate(CACHE_SIZE);
+            for (p, &v) in cache.iter().enumerate() {
+                pos[v] = Some(p);
+                score[v] = forsyth_score(Some(p), adj[v].len());
+            }
+            best = cache
+                .iter()
+                .flat_map(|&v| adj[v].iter().copied())
+                .map(|g| (tri_score(&tris[g], &score), g))
+                .fold(None, |m: Option<(f32, usize)>, c| match m {
+                    Some(m) if m.0 >= c.0 => Some(m),
+                    _ => Some(c),
+                })
+                .map(|m| m.1);
+        }
+        out.extend_from_slice(&self.indices[tris.len() * 3..]);
+        self.indices = out;
+        self
+    }
+
+    /// Average cache misses per triangle for a FIFO cache of `cache_size`
+    /// entries, the usual ACMR metric. Ranges from about 0.5 for ideal
+    /// orderings to 3.
+    pub fn acmr(
+        &self,
+        cache_size: usize,
+    ) -> f32 {
+        let tris = self.indices.len() / 3;
+        if tris == 0 {
+            return 0.0;
+        }
+        let mut cache = VecDeque::with_capacity(cache_size);
+        let mut misses = 0;
+        for &i in &self.indices[..tris * 3] {
+            if !cache.contains(&i) {
+                misses += 1;
+                if cache.len() == cache_size {
+                    cache.pop_front();
+                }
+                cache.push_back(i);
+            }
+        }
+        misses as f32 / tris as f32
+    }
+}
+
+/// Forsyth's vertex score: recently used vertices and ones with few
+/// triangles left score higher. Vertices without triangles score -1.
+fn forsyth_score(
+    cache_pos: Option<usize>,
+    remaining: usize,
+) -> f32 {
+    if remaining == 0 {
+        return -1.0;
+    }
+    let cache = match cache_pos {
+        Some(p) if p < 3 => 0.75,
+        Some(p) => (1.0 - (p - 3) as f32 / (CACHE_SIZE - 3) as f32).powf(1.5),
+        None => 0.0,
+    };
+    cache + 2.0 * (remaining as f32).powf(-0.5)
+}
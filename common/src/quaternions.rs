@@ -1,6 +1,6 @@
-use crate::{Octonion, Quaternion, WhichFloat};
+use crate::{ApproxEq, EulerOrder, Octonion, Quaternion, WhichFloat};
 use num_traits::{Float, One, Zero};
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::ops::{Add, Div, DivAssign, Mul, Neg, Sub};
 use vulkano::pipeline::vertex::{VertexMember, VertexMemberTy};
 
 impl<T: Float + From<f32>> Quaternion<T> {
@@ -16,7 +16,7 @@ impl<T: Float + From<f32>> Quaternion<T> {
 
     pub fn from_slice(inp: &[T]) -> Self {
         let mut q = Quaternion::zero();
-        q.val.copy_from_slice(&inp);
+        q.val.copy_from_slice(inp);
         q
     }
 
@@ -26,9 +26,16 @@ impl<T: Float + From<f32>> Quaternion<T> {
         }
     }
 
-    pub fn inv(self) -> Quaternion<T> {
-        Self {
-            val: [-self.val[0], -self.val[1], -self.val[2], -self.val[3]],
+    /// Multiplicative inverse, `conj / |q|^2`. Zero input gives non-finite
+    /// components; see [`checked_inverse`](Self::checked_inverse).
+    pub fn inverse(self) -> Quaternion<T> { self.conj() / self.dot(self) }
+
+    pub fn checked_inverse(self) -> Option<Quaternion<T>> {
+        let n2 = self.dot(self);
+        if n2 == T::zero() || !n2.is_finite() {
+            None
+        } else {
+            Some(self.conj() / n2)
         }
     }
 
@@ -39,10 +46,8 @@ impl<T: Float + From<f32>> Quaternion<T> {
         self
     }
 
-    pub fn inv_mut(&mut self) -> &Quaternion<T> {
-        for i in 0..4 {
-            self.val[i] = -self.val[i];
-        }
+    pub fn inverse_mut(&mut self) -> &Quaternion<T> {
+        *self = self.inverse();
         self
     }
 
@@ -65,11 +70,7 @@ impl<T: Float + From<f32>> Quaternion<T> {
             .sqrt()
     }
 
-    pub fn to_vec(&self) -> Vec<T> {
-        let mut v = Vec::<T>::with_capacity(4);
-        v.copy_from_slice(&self.val);
-        v
-    }
+    pub fn to_vec(&self) -> Vec<T> { self.val.to_vec() }
 
     pub fn sum(&self) -> T {
         self.val[0] + self.val[1] + self.val[2] + self.val[3]
@@ -85,6 +86,487 @@ impl<T: Float + From<f32>> Quaternion<T> {
             .map(|(x, y)| *x * *y)
             .fold(T::zero(), |res, val| res + val)
     }
+
+    /// Whether `self` and `other` describe the same rotation, treating `q`
+    /// and `-q` as equal.
+    pub fn rotation_eq(
+        &self,
+        other: &Quaternion<T>,
+        epsilon: T,
+    ) -> bool {
+        self.abs_diff_eq(other, epsilon) || self.abs_diff_eq(&-*other, epsilon)
+    }
+
+    pub fn identity() -> Self {
+        Self {
+            val: [T::one(), T::zero(), T::zero(), T::zero()],
+        }
+    }
+
+    /// Rotates `v` by `self`, assuming `self` is a unit quaternion.
+    pub fn rotate(
+        self,
+        v: [T; 3],
+    ) -> [T; 3] {
+        (self * Quaternion::fom_imag(v) * self.conj()).imag()
+    }
+
+    /// Rotation of `angle` radians about `axis`. The axis does not need to be
+    /// normalized; a zero axis gives the identity.
+    pub fn from_axis_angle(
+        axis: [T; 3],
+        angle: T,
+    ) -> Self {
+        let len =
+            (axis[0] * axis[0] + axis[1] * axis[1] + axis[2] * axis[2]).sqrt();
+        if len == T::zero() {
+            return Self::identity();
+        }
+        let (s, c) = (angle * 0.5.into()).sin_cos();
+        let s = s / len;
+        Self {
+            val: [c, axis[0] * s, axis[1] * s, axis[2] * s],
+        }
+    }
+
+    /// Unit axis and angle in `[0, pi]`. The identity yields the X axis and a
+    /// zero angle.
+    pub fn to_axis_angle(self) -> ([T; 3], T) {
+        let q = if self.val[0] < T::zero() {
+            -self.u()
+        } else {
+            self.u()
+        };
+        let s = q.imag();
+        let len = (s[0] * s[0] + s[1] * s[1] + s[2] * s[2]).sqrt();
+        if len <= T::epsilon() {
+            return ([T::one(), T::zero(), T::zero()], T::zero());
+        }
+        let two: T = 2.0.into();
+        (
+            [s[0] / len, s[1] / len, s[2] / len],
+            two * len.atan2(q.val[0]),
+        )
+    }
+
+    pub fn from_euler(
+        order: EulerOrder,
+        angles: [T; 3],
+    ) -> Self {
+        let mut q = Self::identity();
+        for (&axis, &angle) in order.axes().iter().zip(angles.iter()) {
+            let mut a = [T::zero(); 3];
+            a[axis] = T::one();
+            q = Self::from_axis_angle(a, angle) * q;
+        }
+        q
+    }
+
+    /// Euler angles for `order`, each in `[-pi, pi]`. The middle angle is in
+    /// `[-pi/2, pi/2]` for Tait-Bryan orders and `[0, pi]` for proper Euler
+    /// orders. In gimbal lock the third angle is set to zero.
+    ///
+    /// Follows Bernardes & Viollet, "Quaternion to Euler angles conversion: A
+    /// direct, general and computationally efficient method" (2022).
+    pub fn to_euler(
+        self,
+        order: EulerOrder,
+    ) -> [T; 3] {
+        let two: T = 2.0.into();
+        let pi = (-T::one()).acos();
+        let eps = T::epsilon().sqrt();
+        let [i, j, k] = order.axes();
+        let symmetric = i == k;
+        let k = if symmetric { 3 - i - j } else { k };
+        let sign = if j == (i + 1) % 3 {
+            T::one()
+        } else {
+            -T::one()
+        };
+        let q = self.u();
+        let w = q.val[0];
+        let v = q.imag();
+        let (a, b, c, d) = if symmetric {
+            (w, v[i], v[j], v[k] * sign)
+        } else {
+            (w - v[j], v[i] + v[k] * sign, v[j] + w, v[k] * sign - v[i])
+        };
+
+        let mut angles = [T::zero(); 3];
+        angles[1] = two * c.hypot(d).atan2(a.hypot(b));
+        let half_sum = b.atan2(a);
+        let half_diff = d.atan2(c);
+        if angles[1].abs() <= eps {
+            angles[0] = two * half_sum;
+        } else if (angles[1] - pi).abs() <= eps {
+            angles[0] = -two * half_diff;
+        } else {
+            angles[0] = half_sum - half_diff;
+            angles[2] = half_sum + half_diff;
+        }
+        if !symmetric {
+            angles[2] = angles[2] * sign;
+            angles[1] = angles[1] - pi / two;
+        }
+        for a in angles.iter_mut() {
+            if *a > pi {
+                *a = *a - two * pi;
+            } else if *a < -pi {
+                *a = *a + two * pi;
+            }
+        }
+        angles
+    }
+
+    /// Column-major rotation matrix, `m[col][row]`, as GLSL lays out `mat3`.
+    /// Non-unit quaternions are normalized first.
+    pub fn to_mat3(self) -> [[T; 3]; 3] {
+        let n2 = self.dot(self);
+        if n2 == T::zero() {
+            return [
+                [T::one(), T::zero(), T::zero()],
+                [T::zero(), T::one(), T::zero()],
+                [T::zero(), T::zero(), T::one()],
+            ];
+        }
+        let s = Into::<T>::into(2.0) / n2;
+        let [w, x, y, z] = self.val;
+        let one = T::one();
+        [
+            [
+                one - s * (y * y + z * z),
+                s * (x * y + w * z),
+                s * (x * z - w * y),
+            ],
+            [
+                s * (x * y - w * z),
+                one - s * (x * x + z * z),
+                s * (y * z + w * x),
+            ],
+            [
+                s * (x * z + w * y),
+                s * (y * z - w * x),
+                one - s * (x * x + y * y),
+            ],
+        ]
+    }
+
+    pub fn to_mat4(self) -> [[T; 4]; 4] {
+        let m = self.to_mat3();
+        let mut out = [[T::zero(); 4]; 4];
+        for c in 0..3 {
+            out[c][0..3].copy_from_slice(&m[c]);
+        }
+        out[3][3] = T::one();
+        out
+    }
+
+    /// Inverse of [`to_mat3`](Self::to_mat3); `m` must be a pure rotation.
+    pub fn from_mat3(m: [[T; 3]; 3]) -> Self {
+        let one = T::one();
+        let quarter: T = 0.25.into();
+        let two: T = 2.0.into();
+        let r = |row: usize, col: usize| m[col][row];
+        let trace = r(0, 0) + r(1, 1) + r(2, 2);
+        let val = if trace > T::zero() {
+            let s = (trace + one).sqrt() * two;
+            [
+                quarter * s,
+                (r(2, 1) - r(1, 2)) / s,
+                (r(0, 2) - r(2, 0)) / s,
+                (r(1, 0) - r(0, 1)) / s,
+            ]
+        } else if r(0, 0) > r(1, 1) && r(0, 0) > r(2, 2) {
+            let s = (one + r(0, 0) - r(1, 1) - r(2, 2)).sqrt() * two;
+            [
+                (r(2, 1) - r(1, 2)) / s,
+                quarter * s,
+                (r(0, 1) + r(1, 0)) / s,
+                (r(0, 2) + r(2, 0)) / s,
+            ]
+        } else if r(1, 1) > r(2, 2) {
+            let s = (one + r(1, 1) - r(0, 0) - r(2, 2)).sqrt() * two;
+            [
+                (r(0, 2) - r(2, 0)) / s,
+                (r(0, 1) + r(1, 0)) / s,
+                quarter * s,
+                (r(1, 2) + r(2, 1)) / s,
+            ]
+        } else {
+            let s = (one + r(2, 2) - r(0, 0) - r(1, 1)).sqrt() * two;
+            [
+                (r(1, 0) - r(0, 1)) / s,
+                (r(0, 2) + r(2, 0)) / s,
+                (r(1, 2) + r(2, 1)) / s,
+                quarter * s,
+            ]
+        };
+        Self { val }
+    }
+
+    pub fn from_mat4(m: [[T; 4]; 4]) -> Self {
+        let mut r = [[T::zero(); 3]; 3];
+        for c in 0..3 {
+            r[c].copy_from_slice(&m[c][0..3]);
+        }
+        Self::from_mat3(r)
+    }
+
+    /// Shortest rotation taking the direction of `from` onto that of `to`.
+    /// Opposite vectors turn by `pi` about an arbitrary perpendicular axis.
+    pub fn from_rotation_arc(
+        from: [T; 3],
+        to: [T; 3],
+    ) -> Self {
+        let a = normalized(from);
+        let b = normalized(to);
+        // |a + b| = 2 cos(angle / 2) stays accurate where 1 + a.b cancels.
+        let h = [a[0] + b[0], a[1] + b[1], a[2] + b[2]];
+        let s = dot3(h, h).sqrt();
+        if s <= T::epsilon() {
+            let mut axis = cross3([T::one(), T::zero(), T::zero()], a);
+            if dot3(axis, axis) < T::epsilon().sqrt() {
+                axis = cross3([T::zero(), T::one(), T::zero()], a);
+            }
+            return Self::from_axis_angle(axis, (-T::one()).acos());
+        }
+        let c = cross3(a, b);
+        let half: T = 0.5.into();
+        Quaternion::new([s * half, c[0] / s, c[1] / s, c[2] / s]).u()
+    }
+
+    /// Orientation whose local `-z` points along `forward` and whose local `y`
+    /// leans towards `up`, matching the camera in `Mat4::look_at`. If `up` is
+    /// parallel to `forward` another up vector is picked.
+    pub fn look_rotation(
+        forward: [T; 3],
+        up: [T; 3],
+    ) -> Self {
+        let f = normalized(forward);
+        let mut s = cross3(f, up);
+        if dot3(s, s) < T::epsilon() {
+            s = cross3(f, [T::zero(), T::zero(), T::one()]);
+            if dot3(s, s) < T::epsilon() {
+                s = cross3(f, [T::one(), T::zero(), T::zero()]);
+            }
+        }
+        let s = normalized(s);
+        let u = cross3(s, f);
+        Self::from_mat3([s, u, [-f[0], -f[1], -f[2]]])
+    }
+
+    /// Splits `self` into `(swing, twist)` with `self == swing * twist`, where
+    /// `twist` rotates about `axis` and `swing` about an axis perpendicular to
+    /// it.
+    pub fn swing_twist(
+        self,
+        axis: [T; 3],
+    ) -> (Quaternion<T>, Quaternion<T>) {
+        let a = normalized(axis);
+        let p = dot3(self.imag(), a);
+        let twist = Quaternion::new([self.val[0], a[0] * p, a[1] * p, a[2] * p]);
+        let n = twist.n();
+        let twist = if n <= T::epsilon() {
+            Self::identity()
+        } else {
+            twist / n
+        };
+        (self * twist.conj(), twist)
+    }
+
+    /// Quaternion exponential, `e^w (cos|v| + v/|v| sin|v|)`.
+    pub fn exp(self) -> Quaternion<T> {
+        let v = self.imag();
+        let theta = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+        let e = self.val[0].exp();
+        if theta <= T::epsilon() {
+            return Quaternion::new([e, e * v[0], e * v[1], e * v[2]]);
+        }
+        let (s, c) = theta.sin_cos();
+        let k = e * s / theta;
+        Quaternion::new([e * c, v[0] * k, v[1] * k, v[2] * k])
+    }
+
+    /// Principal logarithm, `ln|q| + v/|v| atan2(|v|, w)`. Negative reals have
+    /// no preferred axis and get their `pi` on X.
+    pub fn ln(self) -> Quaternion<T> {
+        let v = self.imag();
+        let w = self.val[0];
+        let s = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+        let len = self.n();
+        let n = len.ln();
+        if s <= T::epsilon() * len && w > T::zero() {
+            // atan2(s, w) / s tends to 1 / w.
+            return Quaternion::new([n, v[0] / w, v[1] / w, v[2] / w]);
+        }
+        if s == T::zero() {
+            let x = if w < T::zero() {
+                (-T::one()).acos()
+            } else {
+                v[0]
+            };
+            return Quaternion::new([n, x, v[1], v[2]]);
+        }
+        // Normalizing first keeps tiny `s` from overflowing `angle / s`.
+        let a = s.atan2(w);
+        Quaternion::new([n, v[0] / s * a, v[1] / s * a, v[2] / s * a])
+    }
+
+    /// `self^t`, computed as `exp(t ln(self))`. For unit quaternions this
+    /// scales the rotation angle by `t`.
+    pub fn powf(
+        self,
+        t: T,
+    ) -> Quaternion<T> {
+        (self.ln() * t).exp()
+    }
+
+    /// Advances an orientation by a world-frame angular velocity (radians per
+    /// second about each axis) over `dt` seconds. The result is renormalized.
+    pub fn integrate(
+        self,
+        angular_velocity: [T; 3],
+        dt: T,
+    ) -> Quaternion<T> {
+        let h = dt * 0.5.into();
+        let w = angular_velocity;
+        (Quaternion::fom_imag([w[0] * h, w[1] * h, w[2] * h]).exp() * self).u()
+    }
+
+    /// Normalized linear interpolation along the shorter arc. Cheaper than
+    /// [`slerp`](Self::slerp) but not constant speed.
+    pub fn nlerp(
+        self,
+        other: Quaternion<T>,
+        t: T,
+    ) -> Quaternion<T> {
+        let other = if self.dot(other) < T::zero() {
+            -other
+        } else {
+            other
+        };
+        (self * (T::one() - t) + other * t).u()
+    }
+
+    /// Spherical linear interpolation along the shorter arc. Falls back to
+    /// [`nlerp`](Self::nlerp) when the inputs are nearly parallel.
+    pub fn slerp(
+        self,
+        other: Quaternion<T>,
+        t: T,
+    ) -> Quaternion<T> {
+        let mut other = other;
+        let mut d = self.dot(other);
+        if d < T::zero() {
+            other = -other;
+            d = -d;
+        }
+        if d > 0.9995.into() {
+            return self.nlerp(other, t);
+        }
+        let theta = d.min(T::one()).acos();
+        let s = theta.sin();
+        (self * ((T::one() - t) * theta).sin() + other * (t * theta).sin()) / s
+    }
+
+    /// Spherical cubic interpolation from `self` to `q1` with inner control
+    /// points `a` and `b`, usually from [`squad_tangent`](Self::squad_tangent).
+    pub fn squad(
+        self,
+        a: Quaternion<T>,
+        b: Quaternion<T>,
+        q1: Quaternion<T>,
+        t: T,
+    ) -> Quaternion<T> {
+        let two: T = 2.0.into();
+        self.slerp(q1, t)
+            .slerp(a.slerp(b, t), two * t * (T::one() - t))
+    }
+
+    /// Inner control point at `cur` for a SQUAD spline through unit
+    /// quaternions `prev`, `cur`, `next`.
+    pub fn squad_tangent(
+        prev: Quaternion<T>,
+        cur: Quaternion<T>,
+        next: Quaternion<T>,
+    ) -> Quaternion<T> {
+        let prev = if cur.dot(prev) < T::zero() {
+            -prev
+        } else {
+            prev
+        };
+        let next = if cur.dot(next) < T::zero() {
+            -next
+        } else {
+            next
+        };
+        let inv = cur.conj();
+        let k: T = (-0.25).into();
+        let l = ((inv * next).ln() + (inv * prev).ln()) * k;
+        (cur * l.exp()).u()
+    }
+}
+
+fn dot3<T: Float>(
+    a: [T; 3],
+    b: [T; 3],
+) -> T {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross3<T: Float>(
+    a: [T; 3],
+    b: [T; 3],
+) -> [T; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalized<T: Float>(a: [T; 3]) -> [T; 3] {
+    let len = dot3(a, a).sqrt();
+    if len == T::zero() {
+        a
+    } else {
+        [a[0] / len, a[1] / len, a[2] / len]
+    }
+}
+
+impl EulerOrder {
+    pub const ALL: [EulerOrder; 12] = [
+        EulerOrder::XYZ,
+        EulerOrder::XZY,
+        EulerOrder::YXZ,
+        EulerOrder::YZX,
+        EulerOrder::ZXY,
+        EulerOrder::ZYX,
+        EulerOrder::XYX,
+        EulerOrder::XZX,
+        EulerOrder::YXY,
+        EulerOrder::YZY,
+        EulerOrder::ZXZ,
+        EulerOrder::ZYZ,
+    ];
+
+    /// Axis indices (0 = X, 1 = Y, 2 = Z) in the order they are applied.
+    pub fn axes(self) -> [usize; 3] {
+        match self {
+            EulerOrder::XYZ => [0, 1, 2],
+            EulerOrder::XZY => [0, 2, 1],
+            EulerOrder::YXZ => [1, 0, 2],
+            EulerOrder::YZX => [1, 2, 0],
+            EulerOrder::ZXY => [2, 0, 1],
+            EulerOrder::ZYX => [2, 1, 0],
+            EulerOrder::XYX => [0, 1, 0],
+            EulerOrder::XZX => [0, 2, 0],
+            EulerOrder::YXY => [1, 0, 1],
+            EulerOrder::YZY => [1, 2, 1],
+            EulerOrder::ZXZ => [2, 0, 2],
+            EulerOrder::ZYZ => [2, 1, 2],
+        }
+    }
 }
 
 impl<T: Float + From<f32>> From<[T; 3]> for Quaternion<T> {
@@ -264,19 +746,31 @@ impl<T: Float + From<f32>> Neg for Quaternion<T> {
         }
     }
 }
-// impl<T: Float> Div<Quaternion<T>> for Quaternion<T> {
-//     type Output = Quaternion<T>;
-//
-//     fn div(
-//         self,
-//         rhs: Quaternion<T>,
-//     ) -> Quaternion<T> {
-//         let a = self.val[0] / rhs.val[0];
-//         let b = self.val[1] / rhs.val[1];
-//         let c = self.val[2] / rhs.val[2];
-//         let d = self.val[3] / rhs.val[3];
-//         Quaternion {
-//             val: [a - b - c - d, a + b + c - d, a - b + c + d, a + b - c +
-// d],         }
-//     }
-// }
+impl<T: Float + From<f32>> Div<Quaternion<T>> for Quaternion<T> {
+    type Output = Quaternion<T>;
+
+    /// Right division, `self * rhs^-1`.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(
+        self,
+        rhs: Quaternion<T>,
+    ) -> Quaternion<T> {
+        self * rhs.inverse()
+    }
+}
+impl<T: Float + From<f32>> DivAssign<T> for Quaternion<T> {
+    fn div_assign(
+        &mut self,
+        rhs: T,
+    ) {
+        *self = *self / rhs;
+    }
+}
+impl<T: Float + From<f32>> DivAssign<Quaternion<T>> for Quaternion<T> {
+    fn div_assign(
+        &mut self,
+        rhs: Quaternion<T>,
+    ) {
+        *self = *self / rhs;
+    }
+}
@@ -0,0 +1,71 @@
+//! Serde support behind the `serde` feature. Quaternion, vector and matrix
+//! types serialize as bare arrays through `#[serde(transparent)]`; the types
+//! below are flattened to arrays of their real components by hand.
+use crate::{CayleyDickson, DualQuaternion, Hypercomplex, Octonion};
+use num_traits::Float;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+impl<T: Float + From<f32> + Serialize> Serialize for Octonion<T> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.as_array().serialize(serializer)
+    }
+}
+impl<'de, T: Float + From<f32> + Deserialize<'de>> Deserialize<'de>
+    for Octonion<T>
+{
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D
+    ) -> Result<Self, D::Error> {
+        <[T; 8]>::deserialize(deserializer).map(Octonion::new)
+    }
+}
+
+impl<T: Float + From<f32> + Serialize> Serialize for DualQuaternion<T> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Octonion::from(*self).serialize(serializer)
+    }
+}
+impl<'de, T: Float + From<f32> + Deserialize<'de>> Deserialize<'de>
+    for DualQuaternion<T>
+{
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D
+    ) -> Result<Self, D::Error> {
+        Octonion::deserialize(deserializer).map(DualQuaternion::from)
+    }
+}
+
+impl<A: Hypercomplex> Serialize for CayleyDickson<A>
+where
+    A::Scalar: Serialize,
+{
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.to_vec().serialize(serializer)
+    }
+}
+impl<'de, A: Hypercomplex> Deserialize<'de> for CayleyDickson<A>
+where
+    A::Scalar: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D
+    ) -> Result<Self, D::Error> {
+        let v = Vec::<A::Scalar>::deserialize(deserializer)?;
+        if v.len() != Self::DIM {
+            return Err(D::Error::invalid_length(
+                v.len(),
+                &format!("{} components", Self::DIM).as_str(),
+            ));
+        }
+        Ok(Self::from_components(&v))
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{BatchFloat, Quaternion, Soa3};
+use num_traits::Float;
+
+impl<T: Float + From<f32>> Soa3<T> {
+    pub fn from_points(points: &[[T; 3]]) -> Self {
+        Self {
+            x: points.iter().map(|p| p[0]).collect(),
+            y: points.iter().map(|p| p[1]).collect(),
+            z: points.iter().map(|p| p[2]).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize { self.x.len() }
+
+    pub fn is_empty(&self) -> bool { self.x.is_empty() }
+
+    pub fn point(
+        &self,
+        i: usize,
+    ) -> [T; 3] {
+        [self.x[i], self.y[i], self.z[i]]
+    }
+
+    pub fn to_points(&self) -> Vec<[T; 3]> {
+        (0..self.len()).map(|i| self.point(i)).collect()
+    }
+
+    /// Copies back into `points`, which has to be as long as `self`.
+    pub fn write_points(
+        &self,
+        points: &mut [[T; 3]],
+    ) {
+        assert_eq!(points.len(), self.len());
+        for (i, p) in points.iter_mut().enumerate() {
+            *p = self.point(i);
+        }
+    }
+}
+
+impl<T: BatchFloat> Soa3<T> {
+    /// `m * v` for every vector; `m` is column-major.
+    pub fn transform(
+        &mut self,
+        m: &[[T; 3]; 3],
+    ) -> &mut Self {
+        T::transform_soa(m, &mut self.x, &mut self.y, &mut self.z);
+        self
+    }
+
+    /// Rotates every vector by the unit quaternion `rotator`.
+    pub fn rotate(
+        &mut self,
+        rotator: Quaternion<T>,
+    ) -> &mut Self {
+        self.transform(&rotator.to_mat3())
+    }
+}
+
+/// Portable structure-of-arrays kernel. The lanes are contiguous, so the
+/// compiler can vectorize the loop for any `T`.
+pub fn transform_lanes<T: Float>(
+    m: &[[T; 3]; 3],
+    x: &mut [T],
+    y: &mut [T],
+    z: &mut [T],
+) {
+    assert!(x.len() == y.len() && y.len() == z.len());
+    for ((x, y), z) in x.iter_mut().zip(y.iter_mut()).zip(z.iter_mut()) {
+        let (a, b, c) = (*x, *y, *z);
+        *x = m[0][0] * a + m[1][0] * b + m[2][0] * c;
+        *y = m[0][1] * a + m[1][1] * b + m[2][1] * c;
+        *z = m[0][2] * a + m[1][2] * b + m[2][2] * c;
+    }
+}
+
+/// `m * v` for each vector of an array-of-structures slice, one at a time.
+pub fn transform_scalar<T: Float>(
+    m: &[[T; 3]; 3],
+    v: &mut [[T; 3]],
+) {
+    for p in v.iter_mut() {
+        let [x, y, z] = *p;
+        p[0] = m[0][0] * x + m[1][0] * y + m[2][0] * z;
+        p[1] = m[0][1] * x + m[1][1] * y + m[2][1] * z;
+        p[2] = m[0][2] * x + m[1][2] * y + m[2][2] * z;
+    }
+}
+
+/// SSE kernel, four vectors per step loaded straight from the lanes. SSE is
+/// part of the x86_64 baseline so no runtime detection is needed.
+#[cfg(target_arch = "x86_64")]
+fn transform_sse(
+    m: &[[f32; 3]; 3],
+    x: &mut [f32],
+    y: &mut [f32],
+    z: &mut [f32],
+) {
+    use std::arch::x86_64::*;
+    let n = x.len();
+    assert!(y.len() == n && z.len() == n);
+    let body = n - n % 4;
+    // SAFETY: SSE is always available on x86_64, and every unaligned load
+    // and store covers `i..i + 4` with `i + 4 <= body <= n` on each lane.
+    unsafe {
+        let col = |c: usize, r: usize| _mm_set1_ps(m[c][r]);
+        let (m00, m01, m02) = (col(0, 0), col(0, 1), col(0, 2));
+        let (m10, m11, m12) = (col(1, 0), col(1, 1), col(1, 2));
+        let (m20, m21, m22) = (col(2, 0), col(2, 1), col(2, 2));
+        for i in (0..body).step_by(4) {
+            let (px, py, pz) = (
+                x.as_mut_ptr().add(i),
+                y.as_mut_ptr().add(i),
+                z.as_mut_ptr().add(i),
+            );
+            let (a, b, c) =
+                (_mm_loadu_ps(px), _mm_loadu_ps(py), _mm_loadu_ps(pz));
+            let row = |r0, r1, r2| {
+                _mm_add_ps(
+                    _mm_add_ps(_mm_mul_ps(r0, a), _mm_mul_ps(r1, b)),
+                    _mm_mul_ps(r2, c),
+                )
+            };
+            let (nx, ny, nz) =
+                (row(m00, m10, m20), row(m01, m11, m21), row(m02, m12, m22));
+            _mm_storeu_ps(px, nx);
+            _mm_storeu_ps(py, ny);
+            _mm_storeu_ps(pz, nz);
+        }
+    }
+    transform_lanes(m, &mut x[body..], &mut y[body..], &mut z[body..]);
+}
+
+impl BatchFloat for f32 {
+    #[cfg(target_arch = "x86_64")]
+    fn transform_soa(
+        m: &[[f32; 3]; 3],
+        x: &mut [f32],
+        y: &mut [f32],
+        z: &mut [f32],
+    ) {
+        transform_sse(m, x, y, z)
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    fn transform_soa(
+        m: &[[f32; 3]; 3],
+        x: &mut [f32],
+        y: &mut [f32],
+        z: &mut [f32],
+    ) {
+        transform_lanes(m, x, y, z)
+    }
+}
+impl BatchFloat for f64 {
+    fn transform_soa(
+        m: &[[f64; 3]; 3],
+        x: &mut [f64],
+        y: &mut [f64],
+        z: &mut [f64],
+    ) {
+        transform_lanes(m, x, y, z)
+    }
+}
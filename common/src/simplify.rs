@@ -0,0 +1,315 @@
+use crate::{Lod, Mesh, Vec3};
+use num_traits::Float;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Weight of the planes that hold open borders in place, relative to the
+/// planes of the faces.
+const BORDER_WEIGHT: f64 = 10.0;
+
+impl<T: Float + From<f32>> Mesh<T> {
+    /// Quadric error edge collapse. Collapses the cheapest edges until at
+    /// most `target_triangles` remain or every collapse left would move the
+    /// surface by more than `max_error`, and returns the largest error
+    /// accepted. Errors are the root of the summed squared distances to the
+    /// planes a vertex has absorbed, so they are in mesh units.
+    ///
+    /// Only `indices` change: each collapse moves a vertex onto a neighbour,
+    /// so no attribute is interpolated and vertices may become unused.
+    /// Vertices sharing a position move together along matching edges, which
+    /// keeps UV seams and hard edges intact, and open borders only collapse
+    /// along themselves. Triangles with an index past the last vertex are
+    /// dropped, here and in [`Mesh::generate_lods`].
+    pub fn simplify(
+        &mut self,
+        target_triangles: usize,
+        max_error: T,
+    ) -> T {
+        let (indices, error) =
+            self.simplified(&self.indices, target_triangles, max_error);
+        self.indices = indices;
+        error
+    }
+
+    /// Replaces `lods` with up to `levels` simplifications of `indices`, each
+    /// aiming at `ratio` times the triangles of the one before. Stops early
+    /// once `max_error` keeps a level from getting any coarser.
+    pub fn generate_lods(
+        &mut self,
+        levels: usize,
+        ratio: f32,
+        max_error: T,
+    ) -> &[Lod<T>] {
+        self.lods.clear();
+        let mut target = (self.indices.len() / 3) as f32;
+        for _ in 0..levels {
+            target *= ratio;
+            let (indices, error) =
+                self.simplified(&self.indices, target as usize, max_error);
+            if indices.len() >= self.lod(self.lods.len()).len() {
+                break;
+            }
+            self.lods.push(Lod { indices, error });
+        }
+        &self.lods
+    }
+
+    /// Index list of `level`, 0 being `indices` itself. Levels past the
+    /// coarsest give the coarsest.
+    pub fn lod(
+        &self,
+        level: usize,
+    ) -> &[u32] {
+        match level.min(self.lods.len()) {
+            0 => &self.indices,
+            l => &self.lods[l - 1].indices,
+        }
+    }
+
+    fn simplified(
+        &self,
+        indices: &[u32],
+        target: usize,
+        max_error: T,
+    ) -> (Vec<u32>, T) {
+        // Quadrics lose too much to cancellation in f32.
+        let pos: Vec<Vec3<f64>> = self
+            .positions
+            .iter()
+            .map(|p| Vec3::new(p.map(|x| x.to_f64().unwrap_or(f64::NAN))))
+            .collect();
+        let mut class = vec![0; pos.len()];
+        let mut members: Vec<Vec<u32>> = vec![];
+        let mut seen = HashMap::new();
+        for (v, p) in pos.iter().enumerate() {
+            let key = p.val.map(|x| (x + 0.0).to_bits());
+            let c = *seen.entry(key).or_insert_with(|| {
+                members.push(vec![]);
+                members.len() - 1
+            });
+            class[v] = c;
+            members[c].push(v as u32);
+        }
+        let at = |c: usize| pos[members[c][0] as usize];
+        let distinct = |t: &[u32; 3]| {
+            let c = t.map(|v| class[v as usize]);
+            c[0] != c[1] && c[1] != c[2] && c[0] != c[2]
+        };
+        let normal = |p: [Vec3<f64>; 3]| (p[1] - p[0]).cross(p[2] - p[0]);
+        let mut tris: Vec<[u32; 3]> = indices
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .filter(|t| t.iter().all(|&v| (v as usize) < pos.len()))
+            .filter(|t| distinct(t))
+            .collect();
+
+        let mut quadric = vec![Quadric::default(); members.len()];
+        for t in &tris {
+            let p = t.map(|v| pos[v as usize]);
+            let n = normal(p).normalize();
+            for &v in t {
+                quadric[class[v as usize]].add_plane(n, p[0], 1.0);
+            }
+        }
+        for ((a, b), faces) in class_edges(&tris, &class) {
+            if faces.len() == 1 {
+                let p = tris[faces[0]].map(|v| pos[v as usize]);
+                let side = (at(b) - at(a)).cross(normal(p)).normalize();
+                quadric[a].add_plane(side, at(a), BORDER_WEIGHT);
+                quadric[b].add_plane(side, at(a), BORDER_WEIGHT);
+            }
+        }
+
+        let limit = max_error.to_f64().unwrap_or(0.0).powi(2);
+        let mut error = 0.0f64;
+        while tris.len() > target {
+            let edges = class_edges(&tris, &class);
+            let mut around = vec![vec![]; members.len()];
+            let mut ring = vec![HashSet::new(); members.len()];
+            let mut linked = HashSet::new();
+            for (f, t) in tris.iter().enumerate() {
+                for k in 0..3 {
+                    let (a, b) = (t[k], t[(k + 1) % 3]);
+                    around[class[a as usize]].push(f);
+                    ring[class[a as usize]].insert(class[b as usize]);
+                    ring[class[b as usize]].insert(class[a as usize]);
+                    linked.insert((a, b));
+                    linked.insert((b, a));
+                }
+            }
+            // A vertex on more than one border loop or on a non-manifold
+            // edge stays put.
+            let mut borders = vec![0; members.len()];
+            let mut locked = vec![false; members.len()];
+            for (&(a, b), faces) in &edges {
+                match faces.len() {
+                    1 => {
+                        borders[a] += 1;
+                        borders[b] += 1;
+                    }
+                    2 => {}
+                    _ => {
+                        locked[a] = true;
+                        locked[b] = true;
+                    }
+                }
+            }
+            let mut candidates = vec![];
+            for (&(a, b), faces) in &edges {
+                for &(from, to) in [(a, b), (b, a)].iter() {
+                    let movable = !locked[from] &&
+                        (borders[from] == 0 ||
+                            borders[from] == 2 && faces.len() == 1);
+                    let cost = quadric[from].add(&quadric[to]).error(at(to));
+                    if movable && cost <= limit {
+                        candidates.push((cost, from, to, faces.len()));
+                    }
+                }
+            }
+            candidates.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+            // Collapses within a pass touch disjoint neighbourhoods, so each
+            // one can be checked against the triangles as they were.
+            let mut remap: Vec<u32> = (0..pos.len() as u32).collect();
+            let mut touched = vec![false; members.len()];
+            let mut removed = 0;
+            for &(cost, from, to, faces) in
+                candidates.iter().take(candidates.len() / 3 + 1)
+            {
+                if tris.len() - removed <= target {
+                    break;
+                }
+                if touched[from] || touched[to] {
+                    continue;
+                }
+                // Link condition: only the faces on the edge may close up.
+                if ring[from].intersection(&ring[to]).count() != faces {
+                    continue;
+                }
+                let partners: Option<Vec<(u32, u32)>> = members[from]
+                    .iter()
+                    .filter(|&&a| {
+                        around[from].iter().any(|&f| tris[f].contains(&a))
+                    })
+                    .map(|&a| {
+                        let mut b = members[to]
+                            .iter()
+                            .filter(|&&b| linked.contains(&(a, b)));
+                        match (b.next(), b.next()) {
+                            (Some(&b), None) => Some((a, b)),
+                            _ => None,
+                        }
+                    })
+                    .collect();
+                let partners = match partners {
+                    Some(p) => p,
+                    None => continue,
+                };
+                let flips = around[from].iter().any(|&f| {
+                    let t = tris[f];
+                    if t.iter().any(|&v| class[v as usize] == to) {
+                        return false;
+                    }
+                    let old = t.map(|v| pos[v as usize]);
+                    let new = t.map(|v| {
+                        if class[v as usize] == from {
+                            at(to)
+                        } else {
+                            pos[v as usize]
+                        }
+                    });
+                    normal(old).dot(normal(new)) <= 0.0
+                });
+                if flips {
+                    continue;
+                }
+                for (a, b) in partners {
+                    remap[a as usize] = b;
+                }
+                for &c in ring[from].iter().chain(&[from, to]) {
+                    touched[c] = true;
+                }
+                quadric[to] = quadric[to].add(&quadric[from]);
+                error = error.max(cost);
+                removed += faces;
+            }
+            if removed == 0 {
+                break;
+            }
+            tris = tris
+                .iter()
+                .map(|t| t.map(|v| remap[v as usize]))
+                .filter(|t| distinct(t))
+                .collect();
+        }
+        let error = (error.sqrt() as f32).into();
+        (tris.concat(), error)
+    }
+}
+
+/// Triangles around each edge between two position classes, keyed with
+/// the smaller class first. Ordered, so simplifying is deterministic.
+fn class_edges(
+    tris: &[[u32; 3]],
+    class: &[usize],
+) -> BTreeMap<(usize, usize), Vec<usize>> {
+    let mut edges: BTreeMap<_, Vec<usize>> = BTreeMap::new();
+    for (f, t) in tris.iter().enumerate() {
+        for k in 0..3 {
+            let a = class[t[k] as usize];
+            let b = class[t[(k + 1) % 3] as usize];
+            edges.entry((a.min(b), a.max(b))).or_default().push(f);
+        }
+    }
+    edges
+}
+
+/// Symmetric 4x4 matrix summing `w * (n.p + d)^2` over planes, upper
+/// triangle row by row.
+#[derive(Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn add_plane(
+        &mut self,
+        n: Vec3<f64>,
+        through: Vec3<f64>,
+        w: f64,
+    ) {
+        let p = [n[0], n[1], n[2], -n.dot(through)];
+        let mut k = 0;
+        for i in 0..4 {
+            for j in i..4 {
+                self.0[k] += w * p[i] * p[j];
+                k += 1;
+            }
+        }
+    }
+
+    fn add(
+        &self,
+        other: &Quadric,
+    ) -> Quadric {
+        let mut q = *self;
+        for (a, b) in q.0.iter_mut().zip(&other.0) {
+            *a += b;
+        }
+        q
+    }
+
+    fn error(
+        &self,
+        p: Vec3<f64>,
+    ) -> f64 {
+        let v = [p[0], p[1], p[2], 1.0];
+        let mut e = 0.0;
+        let mut k = 0;
+        for i in 0..4 {
+            for j in i..4 {
+                let twice = if i == j { 1.0 } else { 2.0 };
+                e += twice * self.0[k] * v[i] * v[j];
+                k += 1;
+            }
+        }
+        e.max(0.0)
+    }
+}
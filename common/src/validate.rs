@@ -0,0 +1,104 @@
+use crate::{mesh::DEFAULT_CREASE, Mesh, MeshReport, NormalMode, Vec3};
+use num_traits::{Float, Zero};
+
+impl MeshReport {
+    /// Nothing that would break rendering. Unused vertices are allowed.
+    pub fn is_ok(&self) -> bool {
+        self.out_of_range == 0 &&
+            self.trailing_indices == 0 &&
+            self.non_finite == 0 &&
+            self.degenerate == 0 &&
+            self.mismatched.is_empty()
+    }
+}
+
+impl<T: Float + From<f32>> Mesh<T> {
+    pub fn validate(&self) -> MeshReport {
+        let n = self.positions.len();
+        let finite = |v: usize| self.positions[v].iter().all(|x| x.is_finite());
+        let mut used = vec![false; n];
+        let mut report = MeshReport {
+            trailing_indices: self.indices.len() % 3,
+            non_finite: (0..n).filter(|&v| !finite(v)).count(),
+            mismatched: self.mismatched_attributes(),
+            ..MeshReport::default()
+        };
+        report.out_of_range =
+            self.indices.iter().filter(|&&i| i as usize >= n).count();
+        for t in self.indices.chunks_exact(3) {
+            if t.iter().all(|&i| (i as usize) < n) {
+                for &i in t {
+                    used[i as usize] = true;
+                }
+                if self.degenerate(t) {
+                    report.degenerate += 1;
+                }
+            }
+        }
+        report.unused_vertices = used.iter().filter(|&&u| !u).count();
+        report
+    }
+
+    /// Fixes what [`validate`](Self::validate) finds and returns its report
+    /// from before the repair:
+    /// - mismatched UV sets, colors and tangents are dropped, mismatched
+    ///   normals are regenerated,
+    /// - trailing indices and triangles that are out of range, degenerate or
+    ///   touch a non-finite vertex are dropped,
+    /// - non-finite vertices are removed once nothing uses them.
+    pub fn repair(&mut self) -> MeshReport {
+        let report = self.validate();
+        let n = self.positions.len();
+        let normals_bad = self.normals.len() != n;
+        self.uvs.retain(|set| set.len() == n);
+        if self.colors.len() != n {
+            self.colors.clear();
+        }
+        if self.tangents.len() != n {
+            self.tangents.clear();
+        }
+        if normals_bad {
+            self.normals.clear();
+        }
+
+        let finite: Vec<bool> = self
+            .positions
+            .iter()
+            .map(|p| p.iter().all(|x| x.is_finite()))
+            .collect();
+        let mut indices = Vec::with_capacity(self.indices.len());
+        for t in self.indices.chunks_exact(3) {
+            let ok = t.iter().all(|&i| (i as usize) < n && finite[i as usize]);
+            if ok && !self.degenerate(t) {
+                indices.extend_from_slice(t);
+            }
+        }
+        self.indices = indices;
+        if report.non_finite > 0 {
+            let mut used = vec![false; n];
+            for &i in &self.indices {
+                used[i as usize] = true;
+            }
+            let keep: Vec<u32> = (0..n as u32)
+                .filter(|&v| finite[v as usize] || used[v as usize])
+                .collect();
+            self.renumber(&keep);
+        }
+        if normals_bad && n > 0 {
+            self.compute_normals(NormalMode::Crease(DEFAULT_CREASE));
+        }
+        report
+    }
+
+    // Expects in-range indices.
+    fn degenerate(
+        &self,
+        t: &[u32],
+    ) -> bool {
+        if t[0] == t[1] || t[1] == t[2] || t[0] == t[2] {
+            return true;
+        }
+        let p = |k: usize| Vec3::new(self.positions[t[k] as usize]);
+        (p(1) - p(0)).cross(p(2) - p(0)).is_zero()
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{Quaternion, Vec2, Vec3, Vec4};
+use num_traits::{Float, Zero};
+use std::ops::{Add, Div, Index, IndexMut, Mul, Neg, Sub};
+
+macro_rules! vector_ops {
+    ($V:ident, $n:expr) => {
+        impl<T: Float + From<f32>> $V<T> {
+            pub fn new(val: [T; $n]) -> Self { Self { val } }
+
+            pub fn dot(
+                self,
+                rhs: $V<T>,
+            ) -> T {
+                self.val
+                    .iter()
+                    .zip(rhs.val.iter())
+                    .fold(T::zero(), |acc, (a, b)| acc + *a * *b)
+            }
+
+            pub fn length_sqr(self) -> T { self.dot(self) }
+
+            pub fn length(self) -> T { self.dot(self).sqrt() }
+
+            /// Unit vector in the same direction; the zero vector is returned
+            /// unchanged.
+            pub fn normalize(self) -> Self {
+                let len = self.length();
+                if len == T::zero() {
+                    self
+                } else {
+                    self / len
+                }
+            }
+
+            pub fn lerp(
+                self,
+                other: $V<T>,
+                t: T,
+            ) -> Self {
+                self + (other - self) * t
+            }
+        }
+
+        impl<T: Float + From<f32>> Zero for $V<T> {
+            fn zero() -> Self {
+                Self {
+                    val: [T::zero(); $n],
+                }
+            }
+
+            fn is_zero(&self) -> bool { self.val.iter().all(|v| v.is_zero()) }
+        }
+
+        impl<T: Float + From<f32>> From<[T; $n]> for $V<T> {
+            fn from(val: [T; $n]) -> Self { Self { val } }
+        }
+        impl<T: Float + From<f32>> From<$V<T>> for [T; $n] {
+            fn from(v: $V<T>) -> Self { v.val }
+        }
+
+        impl<T: Float + From<f32>> Index<usize> for $V<T> {
+            type Output = T;
+
+            fn index(
+                &self,
+                i: usize,
+            ) -> &T {
+                &self.val[i]
+            }
+        }
+        impl<T: Float + From<f32>> IndexMut<usize> for $V<T> {
+            fn index_mut(
+                &mut self,
+                i: usize,
+            ) -> &mut T {
+                &mut self.val[i]
+            }
+        }
+
+        impl<T: Float + From<f32>> Add<$V<T>> for $V<T> {
+            type Output = $V<T>;
+
+            fn add(
+                mut self,
+                rhs: $V<T>,
+            ) -> $V<T> {
+                for i in 0..$n {
+                    self.val[i] = self.val[i] + rhs.val[i];
+                }
+                self
+            }
+        }
+        impl<T: Float + From<f32>> Sub<$V<T>> for $V<T> {
+            type Output = $V<T>;
+
+            fn sub(
+                mut self,
+                rhs: $V<T>,
+            ) -> $V<T> {
+                for i in 0..$n {
+                    self.val[i] = self.val[i] - rhs.val[i];
+                }
+                self
+            }
+        }
+        impl<T: Float + From<f32>> Mul<T> for $V<T> {
+            type Output = $V<T>;
+
+            fn mul(
+                mut self,
+                rhs: T,
+            ) -> $V<T> {
+                for v in self.val.iter_mut() {
+                    *v = *v * rhs;
+                }
+                self
+            }
+        }
+        impl<T: Float + From<f32>> Div<T> for $V<T> {
+            type Output = $V<T>;
+
+            fn div(
+                mut self,
+                rhs: T,
+            ) -> $V<T> {
+                for v in self.val.iter_mut() {
+                    *v = *v / rhs;
+                }
+                self
+            }
+        }
+        impl<T: Float + From<f32>> Neg for $V<T> {
+            type Output = $V<T>;
+
+            fn neg(mut self) -> $V<T> {
+                for v in self.val.iter_mut() {
+                    *v = -*v;
+                }
+                self
+            }
+        }
+    };
+}
+
+vector_ops!(Vec2, 2);
+vector_ops!(Vec3, 3);
+vector_ops!(Vec4, 4);
+
+impl<T: Float + From<f32>> Vec3<T> {
+    pub fn cross(
+        self,
+        rhs: Vec3<T>,
+    ) -> Vec3<T> {
+        let [a, b] = [self.val, rhs.val];
+        Vec3::new([
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ])
+    }
+
+    pub fn extend(
+        self,
+        w: T,
+    ) -> Vec4<T> {
+        Vec4::new([self.val[0], self.val[1], self.val[2], w])
+    }
+}
+
+impl<T: Float + From<f32>> Vec4<T> {
+    pub fn truncate(self) -> Vec3<T> {
+        Vec3::new([self.val[0], self.val[1], self.val[2]])
+    }
+}
+
+impl<T: Float + From<f32>> From<Quaternion<T>> for Vec3<T> {
+    fn from(q: Quaternion<T>) -> Self { Vec3::new(q.imag()) }
+}
+impl<T: Float + From<f32>> From<Vec3<T>> for Quaternion<T> {
+    fn from(v: Vec3<T>) -> Self { Quaternion::fom_imag(v.val) }
+}
@@ -1,14 +1,15 @@
 pub mod world;
 
-use common::{managers::AssetManager, Entity, Quaternion};
-use graphics::Graphics;
+use common::{managers::AssetManager, Entity, Quaternion, Renormalize};
+use graphics::{GpuFloat, Graphics};
 use num_traits::Float;
 use winit::{
     event::{Event, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
 };
-pub struct Engine<T: Float + From<f32>> {
-    graphics: Graphics,
+/// Runs a [`World`] and draws it with vertices in the world's precision `T`.
+pub struct Engine<T: GpuFloat> {
+    graphics: Option<Graphics<T>>,
     mouse:    [f64; 2],
     world:    World<T>,
     assets:   AssetManager,
@@ -18,14 +19,15 @@ pub struct Engine<T: Float + From<f32>> {
 pub struct World<T: Float + From<f32>> {
     origin:  Quaternion<T>,
     objects: Vec<Entity<T>>,
+    renorm:  Renormalize,
 }
 
-impl<T: std::fmt::Debug + Float + From<f32>> Engine<T> {
+impl<T: std::fmt::Debug + GpuFloat> Engine<T> {
     pub fn new(event_loop: &EventLoop<()>) -> Self {
         let mouse = [0.0f64; 2];
         let world = Self::create_world();
         let assets = AssetManager::new();
-        let graphics = Graphics::new(event_loop);
+        let graphics = Some(Graphics::new(event_loop));
         Self {
             graphics,
             mouse,
@@ -34,6 +36,27 @@ impl<T: std::fmt::Debug + Float + From<f32>> Engine<T> {
         }
     }
 
+    /// Engine without a window or assets, for simulating `world` on its
+    /// own. `run` only advances the world.
+    pub fn headless(world: World<T>) -> Self {
+        Self {
+            graphics: None,
+            mouse: [0.0; 2],
+            world,
+            assets: AssetManager::default(),
+        }
+    }
+
+    pub fn world(&self) -> &World<T> { &self.world }
+
+    pub fn world_mut(&mut self) -> &mut World<T> { &mut self.world }
+
+    /// Advances the world by one frame.
+    pub fn update(&mut self) -> &mut Self {
+        self.world.update();
+        self
+    }
+
     pub fn setmouse(
         &mut self,
         mouss: [f64; 2],
@@ -78,8 +101,10 @@ impl<T: std::fmt::Debug + Float + From<f32>> Engine<T> {
         //     }
         //     _ => (),
         // });
-        self.graphics
-            .render(recreate_swapchain, &self.assets, self.mouse);
+        self.update();
+        if let Some(graphics) = &mut self.graphics {
+            graphics.render(recreate_swapchain, &self.assets, self.mouse);
+        }
     }
 
     pub fn create_world() -> World<T> {
@@ -88,3 +113,32 @@ impl<T: std::fmt::Debug + Float + From<f32>> Engine<T> {
         world
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn frames_renormalize() {
+        use crate::{Engine, World};
+        use common::{Entity, Quaternion, Renormalize};
+        let mut e = Entity::<f64>::new();
+        e.orient = Quaternion::from_axis_angle([0.0, 1.0, 0.0], 0.3) * 1.001;
+        let mut world = World::new();
+        world.set_renormalize(Renormalize {
+            every:   10,
+            epsilon: 0.0,
+        });
+        world.load_entities(vec![e]);
+        let mut engine = Engine::headless(world);
+        for _ in 0..9 {
+            engine.run(false);
+        }
+        let e = &engine.world().objects()[0];
+        assert_eq!(e.drift.ops, 9);
+        assert!((e.norm_error() - 1e-3).abs() < 1e-6);
+        engine.run(false);
+        let e = &engine.world().objects()[0];
+        assert_eq!(e.drift.count, 1);
+        assert!(e.norm_error() < 1e-9);
+        assert!((engine.world().max_drift() - 1e-3).abs() < 1e-6);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::World;
-use common::{Entity, Quaternion};
+use common::{Entity, Quaternion, Renormalize};
 use num_traits::{Float, Zero};
 
 impl<T: Float + From<f32>> Default for World<T> {
@@ -12,6 +12,7 @@ impl<T: Float + From<f32>> World<T> {
         Self {
             origin: Quaternion::zero(),
             objects,
+            renorm: Renormalize::default(),
         }
     }
 
@@ -28,9 +29,40 @@ impl<T: Float + From<f32>> World<T> {
     ) -> &Self {
         //TODO stuff
         self.objects = e;
+        for o in &mut self.objects {
+            o.renorm = self.renorm;
+        }
         self
     }
 
+    /// Sets the renormalization policy of every object, including ones
+    /// loaded later.
+    pub fn set_renormalize(
+        &mut self,
+        renorm: Renormalize,
+    ) -> &mut Self {
+        self.renorm = renorm;
+        for o in &mut self.objects {
+            o.renorm = renorm;
+        }
+        self
+    }
+
+    pub fn objects(&self) -> &[Entity<T>] { &self.objects }
+
+    /// Advances every object by its orientation.
+    pub fn update(&mut self) -> &mut Self {
+        for o in &mut self.objects {
+            o.rotate();
+        }
+        self
+    }
+
+    /// Largest orientation drift any object has seen.
+    pub fn max_drift(&self) -> f32 {
+        self.objects.iter().fold(0.0, |m, o| m.max(o.drift.max))
+    }
+
     pub fn save_world(&self) {}
 }
 pub fn load_world<T: Float + From<f32>>() -> World<T> { World::new() }
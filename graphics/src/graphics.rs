@@ -1,4 +1,4 @@
-use crate::Graphics;
+use crate::{GpuFloat, Graphics};
 use common::managers::AssetManager;
 use vulkano::command_buffer::DynamicState;
 
@@ -27,7 +27,7 @@ use winit::{
     window::{Window, WindowBuilder},
 };
 
-impl Graphics {
+impl<F: GpuFloat> Graphics<F> {
     pub fn new(eventl: &EventLoop<()>) -> Self {
         let instance = {
             let extensions = vulkano_win::required_extensions();
@@ -1,8 +1,10 @@
 pub mod graphics;
 pub mod renderer;
 pub mod shaders;
+pub mod vertex;
 
-use std::sync::Arc;
+use common::WhichFloat;
+use std::{marker::PhantomData, sync::Arc};
 use vulkano::{
     buffer::{BufferAccess, CpuAccessibleBuffer},
     command_buffer::DynamicState,
@@ -16,15 +18,18 @@ use vulkano::{
 };
 use winit::window::Window;
 
+/// Per-vertex data uploaded to the GPU in the renderer's precision `F`.
+#[repr(C)]
 #[derive(Default, Debug, Clone, Copy)]
-struct Vertex {
-    position: [f32; 4],
-    orient:   [f32; 4],
-    normals:  [f32; 4],
+struct Vertex<F = f32> {
+    position: [F; 4],
+    orient:   [F; 4],
+    normals:  [F; 4],
 }
+#[repr(C)]
 #[derive(Default, Debug, Clone)]
-struct Normal {
-    normal: [f32; 4],
+struct Normal<F = f32> {
+    normal: [F; 4],
 }
 #[derive(Default, Debug, Clone)]
 struct Ubo {
@@ -33,19 +38,32 @@ struct Ubo {
     proj:  [[f32; 4]; 4],
     rot:   [f32; 4],
 }
-vulkano::impl_vertex!(Vertex, position, orient, normals);
-vulkano::impl_vertex!(Normal, normal);
 vulkano::impl_vertex!(Ubo, ar, mouse, proj, rot);
 
-pub struct Renderer {
+/// Float types the renderer can upload vertices in. Each picks the shaders
+/// that read `Vertex<Self>`.
+pub trait GpuFloat: WhichFloat + From<f32> + Send + Sync + 'static {
+    /// Builds the compute pipeline that animates a `Vertex<Self>` buffer and
+    /// the graphics pipeline that draws it.
+    fn pipelines(
+        device: Arc<Device>,
+        render_pass: Arc<dyn RenderPassAbstract + Sync + Send>,
+    ) -> (
+        Arc<dyn ComputePipelineAbstract + Sync + Send>,
+        Arc<dyn GraphicsPipelineAbstract + Sync + Send>,
+    );
+}
+
+pub struct Renderer<F: GpuFloat = f32> {
     pipeline:           Arc<dyn GraphicsPipelineAbstract + Sync + Send>,
     compute_pipeline:   Arc<dyn ComputePipelineAbstract + Sync + Send>,
     // compute_command_buffer: Arc<AutoCommandBuffer>,
     previous_frame_end: Option<Box<dyn GpuFuture + Send + Sync>>,
     data_buffer:        Arc<dyn BufferAccess + Send + Sync>,
     compute_layout:     Arc<UnsafeDescriptorSetLayout>,
+    precision:          PhantomData<F>,
 }
-pub struct Graphics {
+pub struct Graphics<F: GpuFloat = f32> {
     // instance:           Arc<Instance>,
     surface:            Arc<Surface<Window>>,
     device:             Arc<Device>,
@@ -58,5 +76,30 @@ pub struct Graphics {
     images:             Vec<Arc<vulkano::image::SwapchainImage<Window>>>,
     dynamic_state:      DynamicState,
     depth_buffer:       Arc<AttachmentImage>,
-    renderer:           Renderer,
+    renderer:           Renderer<F>,
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn vertex_definitions() {
+        use crate::{
+            shaders::{cs, cs64, vs, vs64},
+            Normal,
+            Vertex,
+        };
+        use std::mem::size_of;
+        use vulkano::pipeline::vertex::{TwoBuffersDefinition, VertexDefinition};
+        let f32s = TwoBuffersDefinition::<Vertex<f32>, Normal<f32>>::new();
+        let (_, attribs) = f32s.definition(&vs::MainInput).unwrap();
+        let offsets: Vec<usize> = attribs.map(|a| a.2.offset).collect();
+        assert_eq!(offsets, vec![0, 16, 32]);
+        let f64s = TwoBuffersDefinition::<Vertex<f64>, Normal<f64>>::new();
+        let (_, attribs) = f64s.definition(&vs64::MainInput).unwrap();
+        let offsets: Vec<usize> = attribs.map(|a| a.2.offset).collect();
+        assert_eq!(offsets, (0..12).map(|i| 8 * i).collect::<Vec<_>>());
+        assert!(f64s.definition(&vs::MainInput).is_err());
+        assert_eq!(size_of::<cs::ty::vert>(), size_of::<Vertex<f32>>());
+        assert_eq!(size_of::<cs64::ty::vert>(), size_of::<Vertex<f64>>());
+    }
 }
@@ -1,12 +1,14 @@
 use crate::{
-    shaders::{cs, fs, vs},
+    shaders::{cs, cs64, fs, vs, vs64},
+    GpuFloat,
     Normal,
     Renderer,
     Ubo,
     Vertex,
 };
 use common::{managers::AssetManager, Quaternion};
-use std::sync::Arc;
+use num_traits::Zero;
+use std::{marker::PhantomData, sync::Arc};
 use vulkano::{
     buffer::{BufferAccess, BufferUsage, CpuAccessibleBuffer},
     command_buffer::{AutoCommandBufferBuilder, CommandBuffer, DynamicState},
@@ -17,7 +19,13 @@ use vulkano::{
     device::{Device, Queue},
     framebuffer::{FramebufferAbstract, RenderPassAbstract, Subpass},
     image::SwapchainImage,
-    pipeline::{vertex::TwoBuffersDefinition, ComputePipeline, GraphicsPipeline},
+    pipeline::{
+        vertex::TwoBuffersDefinition,
+        ComputePipeline,
+        ComputePipelineAbstract,
+        GraphicsPipeline,
+        GraphicsPipelineAbstract,
+    },
     swapchain::{self, AcquireError, Swapchain},
     sync,
     sync::GpuFuture,
@@ -25,23 +33,69 @@ use vulkano::{
 
 use winit::window::Window;
 
-impl Renderer {
+macro_rules! gpu_float {
+    ($f:ty, $cs:ident, $vs:ident) => {
+        impl GpuFloat for $f {
+            fn pipelines(
+                device: Arc<Device>,
+                render_pass: Arc<dyn RenderPassAbstract + Sync + Send>,
+            ) -> (
+                Arc<dyn ComputePipelineAbstract + Sync + Send>,
+                Arc<dyn GraphicsPipelineAbstract + Sync + Send>,
+            ) {
+                let cs = $cs::Shader::load(device.clone()).unwrap();
+                let vs = $vs::Shader::load(device.clone()).unwrap();
+                let fs = fs::Shader::load(device.clone()).unwrap();
+                let compute = Arc::new(
+                    ComputePipeline::new(
+                        device.clone(),
+                        &cs.main_entry_point(),
+                        &(),
+                    )
+                    .expect("failed to create compute pipeline"),
+                );
+                let graphics = Arc::new(
+                    GraphicsPipeline::start()
+                        .vertex_input(TwoBuffersDefinition::<
+                            Vertex<$f>,
+                            Normal<$f>,
+                        >::new())
+                        .vertex_shader(vs.main_entry_point(), ())
+                        .triangle_list()
+                        .viewports_dynamic_scissors_irrelevant(1)
+                        .depth_stencil_simple_depth()
+                        .line_width(1.0)
+                        .fragment_shader(fs.main_entry_point(), ())
+                        .render_pass(Subpass::from(render_pass, 0).unwrap())
+                        .build(device)
+                        .unwrap(),
+                );
+                (compute, graphics)
+            }
+        }
+    };
+}
+gpu_float!(f32, cs, vs);
+// The f64 shaders need the `shaderFloat64` feature and `R64_SFLOAT` vertex
+// attributes.
+gpu_float!(f64, cs64, vs64);
+
+impl<F: GpuFloat> Renderer<F> {
     pub fn new(
         device: Arc<Device>,
         queue: Arc<Queue>,
         images: &[Arc<SwapchainImage<Window>>],
         render_pass: Arc<dyn RenderPassAbstract + Sync + Send>,
     ) -> Self {
-        let cs = cs::Shader::load(device.clone()).unwrap();
-        let vs = vs::Shader::load(device.clone()).unwrap();
-        let fs = fs::Shader::load(device.clone()).unwrap();
+        let (compute_pipeline, pipeline) =
+            F::pipelines(device.clone(), render_pass);
         let data_buffer: Arc<dyn BufferAccess + Send + Sync> = {
             CpuAccessibleBuffer::from_iter(
                 device.clone(),
                 BufferUsage::all(),
                 true,
                 AssetManager::new()
-                    .load::<f32>("teapot")
+                    .load::<F>("teapot")
                     .set_scale(0.15)
                     .model
                     .positions
@@ -50,26 +104,8 @@ impl Renderer {
             )
             .unwrap()
         };
-        let pipeline = Arc::new(
-            GraphicsPipeline::start()
-                // .vertex_input_single_buffer::<Vertex>()
-                .vertex_input(TwoBuffersDefinition::<Vertex,Normal>::new())
-                .vertex_shader(vs.main_entry_point(), ())
-                .triangle_list()
-                .viewports_dynamic_scissors_irrelevant(1)
-                .depth_stencil_simple_depth()
-                .line_width(1.0)
-                .fragment_shader(fs.main_entry_point(), ())
-                .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
-                .build(device.clone())
-                .unwrap(),
-        );
-        let compute_pipeline = Arc::new(
-            ComputePipeline::new(device.clone(), &cs.main_entry_point(), &())
-                .expect("failed to create compute pipeline"),
-        );
         let compute_layout =
-            compute_pipeline.layout().descriptor_set_layout(0).unwrap();
+            compute_pipeline.descriptor_set_layout(0).unwrap().clone();
         let previous_frame_end = Some(Self::create_sync_objects(&device));
 
         Self {
@@ -79,7 +115,8 @@ impl Renderer {
             // compute_command_buffer,
             previous_frame_end,
             data_buffer,
-            compute_layout: compute_layout.clone(),
+            compute_layout,
+            precision: PhantomData,
         }
     }
 
@@ -125,23 +162,16 @@ impl Renderer {
             BufferUsage::all(),
             true,
             {
-                let mut e = input.load::<f32>("teapot");
+                let mut e = input.load::<F>("teapot");
                 e.model.scale = 0.15;
+                let m = e.model.baked();
                 let mut x = vec![];
                 for i in 0..e.len {
-                    x.push(Vertex {
-                        position: (Quaternion::new([
-                            e.model.positions[i][0],
-                            e.model.positions[i][1],
-                            e.model.positions[i][2],
-                            0.0,
-                        ]) * e.model.scale)
-                            .val,
-                        orient:   Quaternion::new([1.0, 0.0, 0.0, 0.0])
-                            .u_mut()
-                            .val,
-                        normals:  [0.0f32; 4],
-                    });
+                    x.push(Vertex::<F>::from_mesh(
+                        m.positions[i],
+                        Quaternion::identity(),
+                        [F::zero(); 3],
+                    ));
                 }
                 x
             }
@@ -223,7 +253,8 @@ impl Renderer {
         //     .unwrap()
         // };
         let (index_buffer, normals_buffer) = {
-            let e = AssetManager::new().load::<f32>("teapot").set_scale(0.15);
+            let e = AssetManager::new().load::<F>("teapot").set_scale(0.15);
+            let m = e.model.baked();
             (
                 CpuAccessibleBuffer::from_iter(
                     device.clone(),
@@ -239,22 +270,11 @@ impl Renderer {
                     {
                         let mut x = vec![];
                         for i in 0..e.len {
-                            x.push(Vertex {
-                                position: (Quaternion::new([
-                                    e.model.positions[i][0],
-                                    e.model.positions[i][1],
-                                    e.model.positions[i][2],
-                                    0.0,
-                                ]) * e.model.scale)
-                                    .val,
-                                orient:   [0.0; 4],
-                                normals:  [
-                                    e.model.normals[i][0],
-                                    e.model.normals[i][1],
-                                    e.model.normals[i][2],
-                                    0.0,
-                                ],
-                            });
+                            x.push(Vertex::<F>::from_mesh(
+                                m.positions[i],
+                                Quaternion::zero(),
+                                m.normals[i],
+                            ));
                         }
                         x
                     }
@@ -0,0 +1,40 @@
+#version 450
+dvec4 quat_conj(dvec4 q){
+  return dvec4(q.x, -q.y, -q.z, -q.w);
+}
+dvec4 quat_mul(dvec4 q1, dvec4 q2){
+  dvec4 qr;
+  qr.x = (q1.x * q2.x) - (q1.y * q2.y) - (q1.z * q2.z) - (q1.w * q2.w);
+  qr.y = (q1.x * q2.y) + (q1.y * q2.x) + (q1.z * q2.w) - (q1.w * q2.z);
+  qr.z = (q1.x * q2.z) - (q1.y * q2.w) + (q1.z * q2.x) + (q1.w * q2.y);
+  qr.w = (q1.x * q2.w) + (q1.y * q2.z) - (q1.z * q2.y) + (q1.w * q2.x);
+  return qr;
+}
+dvec3 rotate(dvec4 pos, dvec4 rotator){
+  return quat_mul(quat_mul(rotator,dvec4(0.0,pos.xyz)),quat_conj(rotator)).yzw;
+}
+struct vert {
+    dvec4 position;
+    dvec4 orient;
+    dvec4 normals;
+};
+struct unidata {
+    vec2 ar;
+    vec2 mouse;
+    mat4 proj;
+    vec4 rot;
+};
+layout(local_size_x = 8, local_size_y = 1, local_size_z = 1) in;
+layout(set = 0, binding = 0) buffer Data {
+	vert data[];
+} buf;
+layout(set = 0, binding = 1) uniform Uni {
+	unidata data;
+} ubo;
+void main() {
+  uint idx = gl_GlobalInvocationID.x;
+  dvec4 orient = buf.data[idx].orient;
+  dvec4 rot = quat_mul(quat_mul(orient,dvec4(ubo.data.rot)),quat_conj(orient));
+  buf.data[idx].position = dvec4(rotate(buf.data[idx].position,rot)/dvec3(ubo.data.ar,1.0),1.0);
+  buf.data[idx].position += dvec4(ubo.data.mouse,0.0,0.0);
+}
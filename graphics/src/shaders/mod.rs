@@ -7,6 +7,12 @@ pub mod vs {
 pub mod fs {
     vulkano_shaders::shader! {ty: "fragment", path:"src/shaders/frag.glsl"}
 }
+pub mod cs64 {
+    vulkano_shaders::shader! {ty: "compute", path: "src/shaders/comp64.glsl"}
+}
+pub mod vs64 {
+    vulkano_shaders::shader! {ty: "vertex", path:"src/shaders/vert64.glsl"}
+}
 // Used to force recompilation of shader change
 #[allow(dead_code)]
 const SHADER1: &str = include_str!("comp.glsl");
@@ -14,3 +20,7 @@ const SHADER1: &str = include_str!("comp.glsl");
 const SHADER2: &str = include_str!("vert.glsl");
 #[allow(dead_code)]
 const SHADER3: &str = include_str!("frag.glsl");
+#[allow(dead_code)]
+const SHADER4: &str = include_str!("comp64.glsl");
+#[allow(dead_code)]
+const SHADER5: &str = include_str!("vert64.glsl");
@@ -0,0 +1,15 @@
+#version 450
+// A dvec4 input takes two locations, which vulkano can't describe; four
+// doubles take one each and match the `[f64; 4]` members of `Vertex<f64>`.
+layout(location = 0) in double position[4];
+layout(location = 4) in double orient[4];
+layout(location = 8) in double normals[4];
+layout(location = 0) out vec4 color;
+
+void main() {
+    //TODO: add HiDPI scaling as push_constant
+    gl_Position = vec4(position[0], position[1], position[2], position[3]);
+    gl_Position.y = -gl_Position.y;
+    gl_Position.z = (gl_Position.z + gl_Position.w) / 2.0;
+    color=gl_Position;
+}
@@ -0,0 +1,63 @@
+use crate::{Normal, Vertex};
+use common::{Quaternion, WhichFloat};
+use num_traits::{cast, Float};
+use std::mem::size_of;
+use vulkano::pipeline::vertex::{self, VertexMemberInfo};
+
+// `impl_vertex!` only accepts concrete types, so the member layout of the
+// generic vertices is spelled out by hand from `WhichFloat`.
+fn member<F: WhichFloat>(index: usize) -> Option<VertexMemberInfo> {
+    Some(VertexMemberInfo {
+        offset:     index * size_of::<[F; 4]>(),
+        ty:         F::vmt(),
+        array_size: 4,
+    })
+}
+
+// SAFETY: `Vertex<F>` is `#[repr(C)]` with three `[F; 4]` fields. They share
+// one alignment and a size that is a multiple of it, so there is no padding
+// and field `i` starts at `i * size_of::<[F; 4]>()`, the offset `member`
+// reports, holding four `F::vmt()` values.
+unsafe impl<F: WhichFloat + Send + Sync + 'static> vertex::Vertex for Vertex<F> {
+    fn member(name: &str) -> Option<VertexMemberInfo> {
+        match name {
+            "position" => member::<F>(0),
+            "orient" => member::<F>(1),
+            "normals" => member::<F>(2),
+            _ => None,
+        }
+    }
+}
+// SAFETY: `Normal<F>` is `#[repr(C)]` with a single `[F; 4]` field at offset
+// 0, which is what `member::<F>(0)` reports.
+unsafe impl<F: WhichFloat + Send + Sync + 'static> vertex::Vertex for Normal<F> {
+    fn member(name: &str) -> Option<VertexMemberInfo> {
+        match name {
+            "normal" => member::<F>(0),
+            _ => None,
+        }
+    }
+}
+
+fn gpu4<T: Float, F: WhichFloat>(v: [T; 4]) -> [F; 4] {
+    let c = |x: T| cast::<T, F>(x).unwrap_or_else(F::nan);
+    [c(v[0]), c(v[1]), c(v[2]), c(v[3])]
+}
+
+impl<F: WhichFloat> Vertex<F> {
+    /// Converts mesh data of any precision into the GPU layout. Expects
+    /// baked positions, see `Mesh::baked`; `position` is uploaded with
+    /// `w = 0`.
+    pub fn from_mesh<T: Float + From<f32>>(
+        position: [T; 3],
+        orient: Quaternion<T>,
+        normal: [T; 3],
+    ) -> Self {
+        let p = position;
+        Self {
+            position: gpu4([p[0], p[1], p[2], T::zero()]),
+            orient:   gpu4(orient.val),
+            normals:  gpu4([normal[0], normal[1], normal[2], T::zero()]),
+        }
+    }
+}